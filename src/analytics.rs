@@ -0,0 +1,189 @@
+use crate::games::GameKind;
+use crate::history::GameRecord;
+use crate::strategy;
+use crate::Move;
+use colored::Colorize;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// How often something happened out of the times it could have.
+#[derive(Default, Clone, Copy)]
+struct Rate {
+    hits: u32,
+    total: u32,
+}
+
+impl Rate {
+    fn count(&mut self, hit: bool) {
+        self.total += 1;
+        if hit {
+            self.hits += 1;
+        }
+    }
+
+    fn percent(&self) -> Option<f32> {
+        if self.total == 0 {
+            None
+        } else {
+            Some(self.hits as f32 / self.total as f32 * 100.0)
+        }
+    }
+}
+
+/// The player's habits, measured over every recorded round. These look at the
+/// moves the player chose, so noise doesn't count against them.
+struct Habits {
+    cooperation: Rate,
+    first_move: Rate,
+    after_betrayal: Rate,
+    retaliation: Rate,
+    forgiveness: Rate,
+}
+
+fn habits(records: &[GameRecord]) -> Habits {
+    let mut habits = Habits {
+        cooperation: Rate::default(),
+        first_move: Rate::default(),
+        after_betrayal: Rate::default(),
+        retaliation: Rate::default(),
+        forgiveness: Rate::default(),
+    };
+
+    for record in records {
+        let rounds = &record.rounds;
+        for (i, round) in rounds.iter().enumerate() {
+            let cooperated = round.intended().0 == Move::Cooperate;
+            habits.cooperation.count(cooperated);
+
+            if i == 0 {
+                habits.first_move.count(cooperated);
+                continue;
+            }
+            let previous = &rounds[i - 1];
+            if previous.computer_move == Move::Defect {
+                habits.after_betrayal.count(cooperated);
+                // Suckered: you cooperated while they defected.
+                if previous.intended().0 == Move::Cooperate {
+                    habits.retaliation.count(!cooperated);
+                }
+            }
+            // They defected, then came back to cooperating.
+            if i >= 2
+                && rounds[i - 2].computer_move == Move::Defect
+                && previous.computer_move == Move::Cooperate
+            {
+                habits.forgiveness.count(cooperated);
+            }
+        }
+    }
+    habits
+}
+
+/// How often each classic strategy would have made the same move as the player,
+/// best match first. Random is left out since it resembles nobody in particular.
+fn resemblance(records: &[GameRecord]) -> Vec<(String, f32)> {
+    let mut matches: Vec<(String, f32)> = strategy::classic_strategies()
+        .into_iter()
+        .filter(|s| s.name() != "Random")
+        .filter_map(|mut candidate| {
+            let mut agreement = Rate::default();
+            for record in records {
+                // Strategies see history as (opponent, own), so the player is "own".
+                let history: Vec<(Move, Move)> = record
+                    .rounds
+                    .iter()
+                    .map(|r| (r.computer_move, r.player_move))
+                    .collect();
+                let mut rng = ChaCha8Rng::seed_from_u64(record.seed);
+
+                candidate.reset();
+                for (i, round) in record.rounds.iter().enumerate() {
+                    let predicted = candidate.next_move(&history[..i], &mut rng);
+                    agreement.count(predicted == round.intended().0);
+                }
+            }
+            agreement
+                .percent()
+                .map(|percent| (candidate.name().to_string(), percent))
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+    matches
+}
+
+fn print_rate(label: &str, rate: Rate, situations: &str) {
+    match rate.percent() {
+        Some(percent) => println!(
+            "  {} {} {}",
+            format!("{:<28}", label).cyan().bold(),
+            format!("{:>5.1}%", percent).bright_cyan(),
+            format!("({} {})", rate.total, situations).bright_black()
+        ),
+        None => println!(
+            "  {} {}",
+            format!("{:<28}", label).cyan().bold(),
+            "not seen yet".bright_black()
+        ),
+    }
+}
+
+/// Prints the player's behavioural profile from their recorded games. Only
+/// Prisoner's Dilemma games count: betrayal and retaliation mean something
+/// else under the other games' payoffs.
+pub fn print(records: &[GameRecord]) {
+    println!("{}", "HOW YOU PLAY (PRISONER'S DILEMMA)".yellow().bold());
+
+    let records: Vec<GameRecord> = records
+        .iter()
+        .filter(|record| record.game == GameKind::PrisonersDilemma)
+        .cloned()
+        .collect();
+    let habits = habits(&records);
+    if habits.cooperation.total == 0 {
+        println!(
+            "  {}",
+            "Finish a Prisoner's Dilemma game to see how you play.".bright_black()
+        );
+        return;
+    }
+
+    print_rate("Cooperation rate:", habits.cooperation, "moves");
+    print_rate("Cooperative first move:", habits.first_move, "games");
+    print_rate(
+        "Cooperation after betrayal:",
+        habits.after_betrayal,
+        "rounds after they defected",
+    );
+    print_rate(
+        "Retaliation rate:",
+        habits.retaliation,
+        "times you were suckered",
+    );
+    print_rate(
+        "Forgiveness rate:",
+        habits.forgiveness,
+        "times they came back to cooperating",
+    );
+
+    let matches = resemblance(&records);
+    if let Some((closest, percent)) = matches.first() {
+        println!();
+        println!(
+            "  {} {} {}",
+            "You play most like:".magenta().bold(),
+            closest.bright_magenta().bold(),
+            format!("({:.0}% of your moves match)", percent).bright_black()
+        );
+        let runners_up: Vec<String> = matches
+            .iter()
+            .skip(1)
+            .take(3)
+            .map(|(name, percent)| format!("{} {:.0}%", name, percent))
+            .collect();
+        println!(
+            "  {}",
+            format!("Then: {}", runners_up.join(", ")).bright_black()
+        );
+    }
+}
@@ -0,0 +1,358 @@
+//! Computer opponents played by external programs, so strategies can be
+//! written in any language.
+//!
+//! Bots are listed in `bots.json` in the working directory:
+//!
+//! ```json
+//! [
+//!   { "name": "My Bot", "command": ["python3", "my_bot.py"] },
+//!   { "name": "Slow Bot", "command": ["./slow_bot"], "timeout_ms": 5000 }
+//! ]
+//! ```
+//!
+//! A listed bot can be picked anywhere a built-in strategy can: the difficulty
+//! menu, `--difficulty`, simulations, tournaments, evolution and the spatial
+//! grid.
+//!
+//! The program is started on its first move and then plays every game it is
+//! picked for, talking over stdin and stdout one line at a time. Before each of
+//! its moves it is sent
+//!
+//! ```text
+//! MOVE <round> <own moves> <opponent moves>
+//! ```
+//!
+//! where the move lists are the game so far as strings of `C` and `D`, oldest
+//! first, or `-` before the first round (`MOVE 3 CD CC`). It must answer with a
+//! line holding `C` or `D` within the timeout (one second unless `timeout_ms`
+//! says otherwise), and flush its output after doing so. Between games it is
+//! sent a line holding `NEW`, which needs no answer; a bot that keeps its own
+//! state should forget it then. Anything it writes to stderr is shown as is.
+//! The program is stopped when it's no longer needed.
+//!
+//! A bot that exits, takes too long or answers anything else cooperates for
+//! the rest of that game, and is restarted for the next one. A warning says
+//! what went wrong, once per bot and run. A program that can't be started at
+//! all isn't tried again.
+//!
+//! A resumed game starts the bot afresh: it learns the game so far from its
+//! next `MOVE` line, and a bot that had failed before the save gets another
+//! chance.
+
+use crate::strategy::{self, Strategy};
+use crate::Move;
+use colored::Colorize;
+use rand::RngCore;
+use serde::Deserialize;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+const BOTS_FILE: &str = "bots.json";
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+/// Played in place of a bot that has failed.
+const FALLBACK: Move = Move::Cooperate;
+
+#[derive(Deserialize)]
+struct BotConfig {
+    name: String,
+    command: Vec<String>,
+    timeout_ms: Option<u64>,
+}
+
+/// Bots that have already been warned about this run, so a bot failing in
+/// every match of a tournament doesn't bury the results in warnings.
+static WARNED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// The bots listed in `bots.json`, read once per run.
+fn configs() -> &'static [BotConfig] {
+    static CONFIGS: OnceLock<Vec<BotConfig>> = OnceLock::new();
+    CONFIGS.get_or_init(load)
+}
+
+fn load() -> Vec<BotConfig> {
+    if !Path::new(BOTS_FILE).exists() {
+        return Vec::new();
+    }
+    let configs: Vec<BotConfig> =
+        match fs::read_to_string(BOTS_FILE).map(|content| serde_json::from_str(&content)) {
+            Ok(Ok(configs)) => configs,
+            _ => {
+                println!(
+                    "{}",
+                    format!("[!] Could not parse {}; ignoring it.", BOTS_FILE).red()
+                );
+                return Vec::new();
+            }
+        };
+
+    let mut taken: Vec<String> = strategy::difficulty_levels()
+        .into_iter()
+        .chain(strategy::classic_strategies())
+        .map(|s| strategy::slug(s.name()))
+        .collect();
+    let mut bots = Vec::new();
+    for config in configs {
+        let slug = strategy::slug(&config.name);
+        let problem = if slug.is_empty() {
+            Some("it needs a name")
+        } else if taken.contains(&slug) {
+            Some("another strategy already has that name")
+        } else if config.command.is_empty() {
+            Some("its command is empty")
+        } else {
+            None
+        };
+        match problem {
+            Some(problem) => println!(
+                "{}",
+                format!(
+                    "[!] Ignoring bot '{}' in {}: {}.",
+                    config.name, BOTS_FILE, problem
+                )
+                .red()
+            ),
+            None => {
+                taken.push(slug);
+                bots.push(config);
+            }
+        }
+    }
+    bots
+}
+
+/// Every bot from `bots.json`, in the order listed.
+pub fn strategies() -> Vec<Box<dyn Strategy>> {
+    configs()
+        .iter()
+        .map(|config| Box::new(ExternalBot::new(config)) as Box<dyn Strategy>)
+        .collect()
+}
+
+fn encode(moves: impl Iterator<Item = Move>) -> String {
+    let text: String = moves
+        .map(|m| match m {
+            Move::Cooperate => 'C',
+            Move::Defect => 'D',
+        })
+        .collect();
+    if text.is_empty() {
+        "-".to_string()
+    } else {
+        text
+    }
+}
+
+/// A running bot program.
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    /// Lines the program has written, read on a separate thread so that
+    /// waiting for one can time out.
+    replies: Receiver<String>,
+}
+
+impl Process {
+    fn start(command: &[String]) -> Result<Process, String> {
+        let mut child = Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("could not be started ({})", e))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Process {
+            child,
+            stdin,
+            replies,
+        })
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|_| self.exited())
+    }
+
+    fn receive(&mut self, timeout: Duration) -> Result<String, String> {
+        match self.replies.recv_timeout(timeout) {
+            Ok(line) => Ok(line),
+            Err(RecvTimeoutError::Timeout) => Err(format!(
+                "took more than {} ms to answer",
+                timeout.as_millis()
+            )),
+            Err(RecvTimeoutError::Disconnected) => Err(self.exited()),
+        }
+    }
+
+    /// Describes why the program stopped talking. Its output usually closes
+    /// just before it exits, so give it a moment to do so.
+    fn exited(&mut self) -> String {
+        for _ in 0..20 {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                return format!("exited ({})", status);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        "stopped answering".to_string()
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+pub struct ExternalBot {
+    name: String,
+    description: String,
+    command: Vec<String>,
+    timeout: Duration,
+    /// Started on the first move, then kept for later games.
+    process: Option<Process>,
+    /// Whether the bot has failed in the current game.
+    failed: bool,
+    /// Whether the program couldn't be started, which a new game won't fix.
+    unstartable: bool,
+}
+
+impl ExternalBot {
+    fn new(config: &BotConfig) -> ExternalBot {
+        ExternalBot {
+            name: config.name.clone(),
+            description: format!("External program: {}", config.command.join(" ")),
+            command: config.command.clone(),
+            timeout: Duration::from_millis(config.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS)),
+            process: None,
+            failed: false,
+            unstartable: false,
+        }
+    }
+
+    fn ask(&mut self, history: &[(Move, Move)]) -> Result<Move, String> {
+        let process = match &mut self.process {
+            Some(process) => process,
+            None => match Process::start(&self.command) {
+                Ok(process) => self.process.insert(process),
+                Err(problem) => {
+                    self.unstartable = true;
+                    return Err(problem);
+                }
+            },
+        };
+
+        process.send(&format!(
+            "MOVE {} {} {}",
+            history.len() + 1,
+            encode(history.iter().map(|&(_, own)| own)),
+            encode(history.iter().map(|&(opponent, _)| opponent))
+        ))?;
+        let reply = process.receive(self.timeout)?;
+        match reply.trim() {
+            "C" | "c" => Ok(Move::Cooperate),
+            "D" | "d" => Ok(Move::Defect),
+            other => Err(format!("answered '{}' instead of C or D", other)),
+        }
+    }
+}
+
+impl Strategy for ExternalBot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        if self.failed || self.unstartable {
+            return FALLBACK;
+        }
+        match self.ask(history) {
+            Ok(choice) => choice,
+            Err(problem) => {
+                self.failed = true;
+                self.process = None;
+                let mut warned = WARNED.lock().unwrap();
+                if !warned.contains(&self.name) {
+                    warned.push(self.name.clone());
+                    println!(
+                        "{}",
+                        format!(
+                            "[!] Bot '{}' {}; it cooperates for the rest of the game. \
+                             Later failures won't be reported.",
+                            self.name, problem
+                        )
+                        .red()
+                    );
+                }
+                FALLBACK
+            }
+        }
+    }
+
+    /// The whole game so far goes out with every move, so there is nothing to
+    /// replay, and replaying would put each earlier move on the clock again.
+    fn resume(&mut self, _history: &[(Move, Move)], _rng: &mut dyn RngCore) {
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        if self.failed {
+            self.process = None;
+            self.failed = false;
+        } else if let Some(process) = &mut self.process {
+            if process.send("NEW").is_err() {
+                self.process = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move::{Cooperate, Defect};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn bot(script: &str) -> ExternalBot {
+        ExternalBot::new(&BotConfig {
+            name: "Test Bot".to_string(),
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            timeout_ms: Some(5000),
+        })
+    }
+
+    #[test]
+    fn resumed_bot_is_sent_the_game_once() {
+        // Answers a single line, and only if it holds the whole game so far.
+        let mut opponent = bot(r#"read line; [ "$line" = "MOVE 3 CD DC" ] && echo D"#);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let history = [(Defect, Cooperate), (Cooperate, Defect)];
+
+        opponent.resume(&history, &mut rng);
+        assert_eq!(opponent.next_move(&history, &mut rng), Defect);
+        assert!(!opponent.failed);
+    }
+}
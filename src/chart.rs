@@ -0,0 +1,126 @@
+use colored::{Color, Colorize};
+
+/// Width of the plotting area, in terminal columns.
+const WIDTH: usize = 50;
+const LABEL_WIDTH: usize = 7;
+
+/// Colors handed out to chart series in order.
+pub const PALETTE: [Color; 7] = [
+    Color::BrightGreen,
+    Color::BrightRed,
+    Color::BrightCyan,
+    Color::BrightYellow,
+    Color::BrightMagenta,
+    Color::BrightBlue,
+    Color::White,
+];
+
+pub struct Series {
+    pub label: String,
+    pub values: Vec<f64>,
+    pub color: Color,
+}
+
+/// Plots every series on a shared y axis, one column per step of x. Longer
+/// series are sampled down to fit the width. Later series are drawn on top.
+pub fn print_line_chart(series: &[Series], height: usize, y_label: &dyn Fn(f64) -> String) {
+    let points = series.iter().map(|s| s.values.len()).max().unwrap_or(0);
+    if points < 2 || height < 2 {
+        return;
+    }
+
+    let values = series.iter().flat_map(|s| s.values.iter().copied());
+    let (low, high) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    let (low, high) = if high > low {
+        (low, high)
+    } else {
+        (low - 1.0, high + 1.0)
+    };
+    let row_of =
+        |value: f64| ((high - value) / (high - low) * (height - 1) as f64).round() as usize;
+
+    let mut grid: Vec<Vec<Option<(char, Color)>>> = vec![vec![None; WIDTH]; height];
+    for s in series {
+        let mut previous: Option<usize> = None;
+        for column in 0..WIDTH {
+            let index = column * (s.values.len() - 1) / (WIDTH - 1);
+            let row = row_of(s.values[index]);
+            // Fill the jump from the previous column so steep changes stay connected.
+            if let Some(previous) = previous {
+                let (top, bottom) = (previous.min(row), previous.max(row));
+                for cells in grid.iter_mut().take(bottom).skip(top + 1) {
+                    cells[column] = Some(('│', s.color));
+                }
+            }
+            grid[row][column] = Some(('•', s.color));
+            previous = Some(row);
+        }
+    }
+
+    for (row, cells) in grid.iter().enumerate() {
+        let label = if row == 0 {
+            y_label(high)
+        } else if row == height - 1 {
+            y_label(low)
+        } else if row == (height - 1) / 2 {
+            y_label(high - (high - low) * row as f64 / (height - 1) as f64)
+        } else {
+            String::new()
+        };
+        let line: String = cells
+            .iter()
+            .map(|cell| match cell {
+                Some((c, color)) => c.to_string().color(*color).to_string(),
+                None => " ".to_string(),
+            })
+            .collect();
+        println!(
+            "  {} {}{}",
+            format!("{:>width$}", label, width = LABEL_WIDTH).bright_black(),
+            "┤".bright_black(),
+            line
+        );
+    }
+    println!(
+        "  {} {}{}",
+        " ".repeat(LABEL_WIDTH),
+        "└".bright_black(),
+        "─".repeat(WIDTH).bright_black()
+    );
+
+    let legend: Vec<String> = series
+        .iter()
+        .map(|s| format!("{} {}", "•".color(s.color), s.label))
+        .collect();
+    println!("  {} {}", " ".repeat(LABEL_WIDTH), legend.join("  "));
+}
+
+/// Horizontal bars scaled to the largest value, drawn with eighth-block
+/// characters so close values are still told apart.
+pub fn print_bar_chart(rows: &[(String, f64, Color)], value_label: &dyn Fn(f64) -> String) {
+    const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    let largest = rows.iter().map(|r| r.1).fold(0.0, f64::max);
+    let name_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+
+    for (name, value, color) in rows {
+        let eighths = if largest > 0.0 {
+            (value.max(0.0) / largest * (WIDTH * 8) as f64).round() as usize
+        } else {
+            0
+        };
+        let mut bar = "█".repeat(eighths / 8);
+        if eighths % 8 > 0 {
+            bar.push(EIGHTHS[eighths % 8]);
+        }
+        println!(
+            "  {:<width$} {} {}",
+            name,
+            bar.color(*color),
+            value_label(*value),
+            width = name_width
+        );
+    }
+}
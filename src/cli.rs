@@ -0,0 +1,650 @@
+use crate::evolution::{self, Dynamics};
+use crate::games::GameKind;
+use crate::network;
+use crate::profile;
+use crate::spatial::{self, Neighborhood};
+use crate::strategy::{self, Strategy};
+use crate::tournament;
+use crate::Length;
+use colored::Colorize;
+use std::path::PathBuf;
+
+pub const DEFAULT_SIMULATE_ROUNDS: u32 = 20;
+
+pub enum Command {
+    Menu,
+    Help,
+    Play {
+        game: GameKind,
+        opponent: Option<Box<dyn Strategy>>,
+        length: Option<Length>,
+        noise: f64,
+        seed: Option<u64>,
+    },
+    Stats,
+    Rules {
+        game: GameKind,
+    },
+    Tournament {
+        rounds: u32,
+        repetitions: u32,
+        noise: f64,
+        seed: Option<u64>,
+    },
+    Simulate {
+        player: Box<dyn Strategy>,
+        opponent: Box<dyn Strategy>,
+        rounds: u32,
+        noise: f64,
+        seed: Option<u64>,
+    },
+    Evolve {
+        dynamics: Dynamics,
+        strategies: Vec<String>,
+        generations: u32,
+        population: u32,
+        mutation: f64,
+        rounds: u32,
+        noise: f64,
+        seed: Option<u64>,
+    },
+    Hotseat {
+        game: GameKind,
+        length: Option<Length>,
+    },
+    Host {
+        port: u16,
+        game: GameKind,
+        length: Option<Length>,
+    },
+    Join {
+        address: String,
+    },
+    Spatial {
+        size: u32,
+        neighborhood: Neighborhood,
+        strategies: Vec<String>,
+        mix: Vec<f64>,
+        generations: u32,
+        rounds: u32,
+        seed: Option<u64>,
+    },
+    ResetStats,
+}
+
+/// `--name value` pairs following the subcommand.
+struct Options {
+    values: Vec<(String, String)>,
+}
+
+impl Options {
+    fn parse(args: &[String], allowed: &[&str]) -> Result<Options, String> {
+        let mut values = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(format!("unexpected argument '{}'", arg));
+            };
+            if !allowed.contains(&name) {
+                return Err(format!("unknown option '--{}'", name));
+            }
+            let Some(value) = iter.next() else {
+                return Err(format!("option '--{}' needs a value", name));
+            };
+            values.push((name.to_string(), value.clone()));
+        }
+        Ok(Options { values })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn number(&self, name: &str, min: u32, max: u32) -> Result<Option<u32>, String> {
+        let Some(value) = self.get(name) else {
+            return Ok(None);
+        };
+        match value.parse::<u32>() {
+            Ok(n) if (min..=max).contains(&n) => Ok(Some(n)),
+            _ => Err(format!(
+                "--{} must be a number between {} and {}",
+                name, min, max
+            )),
+        }
+    }
+
+    fn seed(&self) -> Result<Option<u64>, String> {
+        match self.get("seed") {
+            None => Ok(None),
+            Some(value) => value
+                .parse::<u64>()
+                .map(Some)
+                .map_err(|_| "--seed must be a non-negative integer".to_string()),
+        }
+    }
+
+    /// `--noise` is given in percent; returns the probability, 0 if omitted.
+    fn noise(&self) -> Result<f64, String> {
+        Ok(self.number("noise", 0, 50)?.unwrap_or(0) as f64 / 100.0)
+    }
+
+    /// A percentage that may have decimals, such as `--mutation 0.5`, as a probability.
+    fn percent(&self, name: &str, max: f64) -> Result<Option<f64>, String> {
+        let Some(value) = self.get(name) else {
+            return Ok(None);
+        };
+        match value.parse::<f64>() {
+            Ok(p) if (0.0..=max).contains(&p) => Ok(Some(p / 100.0)),
+            _ => Err(format!(
+                "--{} must be a percentage between 0 and {}",
+                name, max
+            )),
+        }
+    }
+
+    /// A comma-separated list of strategies, as their display names.
+    fn strategies(&self, name: &str) -> Result<Vec<String>, String> {
+        let Some(value) = self.get(name) else {
+            return Ok(Vec::new());
+        };
+        let mut names: Vec<String> = Vec::new();
+        for slug in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let found = strategy::find(slug)
+                .ok_or_else(|| format!("unknown strategy '{}' (see --help for the list)", slug))?;
+            if !names.iter().any(|n| n == found.name()) {
+                names.push(found.name().to_string());
+            }
+        }
+        if names.len() < 2 {
+            return Err(format!(
+                "--{} needs at least two different strategies",
+                name
+            ));
+        }
+        Ok(names)
+    }
+
+    /// Comma-separated non-negative numbers, such as `--mix 90,10`.
+    fn weights(&self, name: &str) -> Result<Vec<f64>, String> {
+        let Some(value) = self.get(name) else {
+            return Ok(Vec::new());
+        };
+        value
+            .split(',')
+            .map(|w| match w.trim().parse::<f64>() {
+                Ok(w) if w >= 0.0 => Ok(w),
+                _ => Err(format!(
+                    "--{} must be numbers separated by commas, such as 90,10",
+                    name
+                )),
+            })
+            .collect()
+    }
+
+    /// `--rounds` or `--continuation` (a percent chance of another round).
+    fn length(&self) -> Result<Option<Length>, String> {
+        let rounds = self.number("rounds", 1, 50)?;
+        let continuation = self.number("continuation", 1, 99)?;
+        match (rounds, continuation) {
+            (Some(_), Some(_)) => {
+                Err("use either --rounds or --continuation, not both".to_string())
+            }
+            (Some(rounds), None) => Ok(Some(Length::Rounds(rounds))),
+            (None, Some(percent)) => Ok(Some(Length::Continuation(percent as f64 / 100.0))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn game(&self) -> Result<GameKind, String> {
+        match self.get("game") {
+            None => Ok(GameKind::PrisonersDilemma),
+            Some(name) => GameKind::from_slug(name).ok_or_else(|| {
+                format!("unknown game '{}' (see --help for the list)", name)
+            }),
+        }
+    }
+
+    fn strategy(&self, name: &str) -> Result<Option<Box<dyn Strategy>>, String> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(value) => strategy::find(value).map(Some).ok_or_else(|| {
+                format!("unknown strategy '{}' (see --help for the list)", value)
+            }),
+        }
+    }
+}
+
+/// Options that apply to every command.
+pub struct Globals {
+    pub profile: Option<String>,
+    pub data_dir: Option<PathBuf>,
+}
+
+/// Removes the global options from anywhere in `args` a flag can go.
+pub fn take_globals(args: &mut Vec<String>) -> Result<Globals, String> {
+    let mut globals = Globals {
+        profile: None,
+        data_dir: None,
+    };
+    // Backwards, so removing one doesn't move the flags still to come, and
+    // the last of a repeated option wins as it does for the others.
+    for position in flag_positions(args).into_iter().rev() {
+        let flag = args[position].clone();
+        if flag != "--profile" && flag != "--data-dir" {
+            continue;
+        }
+        if position + 1 >= args.len() {
+            return Err(format!("option '{}' needs a value", flag));
+        }
+        let value = args.remove(position + 1);
+        args.remove(position);
+        if flag == "--profile" {
+            globals.profile.get_or_insert(value);
+        } else {
+            globals.data_dir.get_or_insert(PathBuf::from(value));
+        }
+    }
+    Ok(globals)
+}
+
+fn is_help(arg: &str) -> bool {
+    arg == "-h" || arg == "--help"
+}
+
+/// Where flags are in `args`, as opposed to option values: every `--name`
+/// takes the argument after it, except for the help flags.
+fn flag_positions(args: &[String]) -> Vec<usize> {
+    let mut positions = Vec::new();
+    let mut position = 0;
+    while position < args.len() {
+        positions.push(position);
+        let arg = &args[position];
+        position += if arg.starts_with("--") && !is_help(arg) {
+            2
+        } else {
+            1
+        };
+    }
+    positions
+}
+
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let Some(command) = args.first() else {
+        return Ok(Command::Menu);
+    };
+    let rest = &args[1..];
+    if flag_positions(rest).into_iter().any(|i| is_help(&rest[i])) {
+        return Ok(Command::Help);
+    }
+
+    match command.as_str() {
+        "-h" | "--help" | "help" => Ok(Command::Help),
+        "play" => {
+            let options = Options::parse(
+                rest,
+                &["game", "difficulty", "rounds", "continuation", "noise", "seed"],
+            )?;
+            Ok(Command::Play {
+                game: options.game()?,
+                opponent: options.strategy("difficulty")?,
+                length: options.length()?,
+                noise: options.noise()?,
+                seed: options.seed()?,
+            })
+        }
+        "hotseat" => {
+            let options = Options::parse(rest, &["game", "rounds", "continuation"])?;
+            Ok(Command::Hotseat {
+                game: options.game()?,
+                length: options.length()?,
+            })
+        }
+        "host" => {
+            let options = Options::parse(rest, &["port", "game", "rounds", "continuation"])?;
+            Ok(Command::Host {
+                port: options
+                    .number("port", 1024, u16::MAX as u32)?
+                    .map_or(network::DEFAULT_PORT, |port| port as u16),
+                game: options.game()?,
+                length: options.length()?,
+            })
+        }
+        "join" => {
+            let options = Options::parse(rest, &["address"])?;
+            let address = options
+                .get("address")
+                .ok_or("join needs --address <HOST:PORT>")?;
+            Ok(Command::Join {
+                address: if address.contains(':') {
+                    address.to_string()
+                } else {
+                    format!("{}:{}", address, network::DEFAULT_PORT)
+                },
+            })
+        }
+        "stats" => {
+            Options::parse(rest, &[])?;
+            Ok(Command::Stats)
+        }
+        "rules" => {
+            let options = Options::parse(rest, &["game"])?;
+            Ok(Command::Rules {
+                game: options.game()?,
+            })
+        }
+        "tournament" => {
+            let options = Options::parse(rest, &["rounds", "repetitions", "noise", "seed"])?;
+            Ok(Command::Tournament {
+                rounds: options
+                    .number("rounds", 1, 100_000)?
+                    .unwrap_or(tournament::DEFAULT_ROUNDS),
+                repetitions: options
+                    .number("repetitions", 1, 10_000)?
+                    .unwrap_or(tournament::DEFAULT_REPETITIONS),
+                noise: options.noise()?,
+                seed: options.seed()?,
+            })
+        }
+        "simulate" => {
+            let options = Options::parse(rest, &["player", "opponent", "rounds", "noise", "seed"])?;
+            Ok(Command::Simulate {
+                player: options
+                    .strategy("player")?
+                    .ok_or("simulate needs --player <STRATEGY>")?,
+                opponent: options
+                    .strategy("opponent")?
+                    .ok_or("simulate needs --opponent <STRATEGY>")?,
+                rounds: options
+                    .number("rounds", 1, 100_000)?
+                    .unwrap_or(DEFAULT_SIMULATE_ROUNDS),
+                noise: options.noise()?,
+                seed: options.seed()?,
+            })
+        }
+        "evolve" => {
+            let options = Options::parse(
+                rest,
+                &[
+                    "dynamics",
+                    "strategies",
+                    "generations",
+                    "population",
+                    "mutation",
+                    "rounds",
+                    "noise",
+                    "seed",
+                ],
+            )?;
+            let dynamics = match options.get("dynamics") {
+                None => Dynamics::Replicator,
+                Some(name) => Dynamics::from_slug(name)
+                    .ok_or_else(|| format!("unknown dynamics '{}' (replicator or moran)", name))?,
+            };
+            if matches!(dynamics, Dynamics::Replicator) && options.get("population").is_some() {
+                return Err("--population only applies to --dynamics moran".to_string());
+            }
+            Ok(Command::Evolve {
+                dynamics,
+                strategies: options.strategies("strategies")?,
+                generations: options
+                    .number("generations", 1, 10_000)?
+                    .unwrap_or(evolution::DEFAULT_GENERATIONS),
+                population: options
+                    .number("population", 2, 1000)?
+                    .unwrap_or(evolution::DEFAULT_POPULATION),
+                mutation: options.percent("mutation", 50.0)?.unwrap_or(0.0),
+                rounds: options
+                    .number("rounds", 1, 10_000)?
+                    .unwrap_or(evolution::DEFAULT_ROUNDS),
+                noise: options.noise()?,
+                seed: options.seed()?,
+            })
+        }
+        "spatial" => {
+            let options = Options::parse(
+                rest,
+                &[
+                    "size",
+                    "neighborhood",
+                    "strategies",
+                    "mix",
+                    "generations",
+                    "rounds",
+                    "seed",
+                ],
+            )?;
+            let neighborhood = match options.get("neighborhood") {
+                None => Neighborhood::Moore,
+                Some(name) => Neighborhood::from_slug(name).ok_or_else(|| {
+                    format!("unknown neighborhood '{}' (von-neumann or moore)", name)
+                })?,
+            };
+            let strategies = options.strategies("strategies")?;
+            if strategies.len() > spatial::MAX_STRATEGIES {
+                return Err(format!(
+                    "--strategies takes at most {} strategies",
+                    spatial::MAX_STRATEGIES
+                ));
+            }
+            let mix = options.weights("mix")?;
+            let count = if strategies.is_empty() {
+                spatial::DEFAULT_STRATEGIES.len()
+            } else {
+                strategies.len()
+            };
+            if !mix.is_empty() && mix.len() != count {
+                return Err(format!(
+                    "--mix needs one number for each of the {} strategies",
+                    count
+                ));
+            }
+            Ok(Command::Spatial {
+                size: options
+                    .number("size", 5, spatial::MAX_SIZE)?
+                    .unwrap_or(spatial::DEFAULT_SIZE),
+                neighborhood,
+                strategies,
+                mix,
+                generations: options
+                    .number("generations", 1, 10_000)?
+                    .unwrap_or(spatial::DEFAULT_GENERATIONS),
+                rounds: options
+                    .number("rounds", 1, 10_000)?
+                    .unwrap_or(spatial::DEFAULT_ROUNDS),
+                seed: options.seed()?,
+            })
+        }
+        "reset-stats" => {
+            Options::parse(rest, &[])?;
+            Ok(Command::ResetStats)
+        }
+        other => Err(format!("unknown command '{}'", other)),
+    }
+}
+
+pub fn print_help() {
+    println!("{}", "GAME THEORY: PRISONER'S DILEMMA - Terminal Edition".bold());
+    println!();
+    println!("{}", "USAGE:".yellow().bold());
+    println!("    game_theory [COMMAND] [OPTIONS]");
+    println!();
+    println!("    Without a command the interactive main menu opens.");
+    println!();
+    println!("{}", "GLOBAL OPTIONS:".yellow().bold());
+    println!("    --profile <NAME>    Play and keep statistics as this profile");
+    println!("    --data-dir <DIR>    Keep profiles and statistics in DIR");
+    println!(
+        "                        (default: ${} or $XDG_DATA_HOME/game_theory)",
+        profile::DATA_DIR_ENV
+    );
+    println!();
+    println!("{}", "COMMANDS:".yellow().bold());
+    println!("    {}          Play against the computer", "play".cyan());
+    println!("        --game <GAME>            Game to play (default: prisoners-dilemma)");
+    println!("        --difficulty <STRATEGY>  Computer opponent (asked if omitted)");
+    println!("        --rounds <1-50>          Rounds per game (asked if omitted)");
+    println!("        --continuation <1-99>    Instead of --rounds: percent chance of");
+    println!("                                 another round after each one");
+    println!("        --noise <0-50>           Percent of moves flipped by mistake (default: 0)");
+    println!("        --seed <N>               Replay the computer's choices from a seed");
+    println!("    {}       Two players taking turns on one keyboard", "hotseat".cyan());
+    println!("        --game <GAME>            Game to play (default: prisoners-dilemma)");
+    println!("        --rounds <1-50>          Rounds per game (asked if omitted)");
+    println!("        --continuation <1-99>    Instead of --rounds: percent chance of");
+    println!("                                 another round after each one");
+    println!("    {}          Host a network game and wait for a player", "host".cyan());
+    println!(
+        "        --port <N>               Port to listen on (default: {})",
+        network::DEFAULT_PORT
+    );
+    println!("        --game <GAME>            Game to play (default: prisoners-dilemma)");
+    println!("        --rounds <1-50>          Rounds per game (asked if omitted)");
+    println!("        --continuation <1-99>    Instead of --rounds: percent chance of");
+    println!("                                 another round after each one");
+    println!("    {}          Join a game someone is hosting", "join".cyan());
+    println!(
+        "        --address <HOST:PORT>    Where the host is (port defaults to {})",
+        network::DEFAULT_PORT
+    );
+    println!("    {}         Show your statistics", "stats".cyan());
+    println!("    {}         Show the rules and payoff matrix", "rules".cyan());
+    println!("        --game <GAME>            Game to explain (default: prisoners-dilemma)");
+    println!("    {}    Round-robin between every strategy", "tournament".cyan());
+    println!(
+        "        --rounds <N>             Rounds per match (default: {})",
+        tournament::DEFAULT_ROUNDS
+    );
+    println!(
+        "        --repetitions <N>        Matches per pairing (default: {})",
+        tournament::DEFAULT_REPETITIONS
+    );
+    println!("        --noise <0-50>           Percent of moves flipped by mistake (default: 0)");
+    println!("        --seed <N>               Reproduce an earlier tournament");
+    println!("    {}      Play two strategies against each other", "simulate".cyan());
+    println!("        --player <STRATEGY>      First strategy");
+    println!("        --opponent <STRATEGY>    Second strategy");
+    println!(
+        "        --rounds <N>             Rounds to play (default: {})",
+        DEFAULT_SIMULATE_ROUNDS
+    );
+    println!("        --noise <0-50>           Percent of moves flipped by mistake (default: 0)");
+    println!("        --seed <N>               Reproduce an earlier simulation");
+    println!("    {}        Evolve a population of strategies", "evolve".cyan());
+    println!("        --dynamics <NAME>        replicator (default) or moran");
+    println!("        --strategies <A,B,...>   Strategies in the population (default: all)");
+    println!(
+        "        --generations <N>        Generations to run (default: {})",
+        evolution::DEFAULT_GENERATIONS
+    );
+    println!(
+        "        --population <2-1000>    Individuals in a Moran population (default: {})",
+        evolution::DEFAULT_POPULATION
+    );
+    println!("        --mutation <0-50>        Percent of offspring born as a random strategy");
+    println!(
+        "        --rounds <N>             Rounds per match (default: {})",
+        evolution::DEFAULT_ROUNDS
+    );
+    println!("        --noise <0-50>           Percent of moves flipped by mistake (default: 0)");
+    println!("        --seed <N>               Reproduce an earlier run");
+    println!("    {}       Strategies spreading across a grid", "spatial".cyan());
+    println!(
+        "        --size <5-{}>            Cells along each side (default: {})",
+        spatial::MAX_SIZE,
+        spatial::DEFAULT_SIZE
+    );
+    println!("        --neighborhood <NAME>    moore (default) or von-neumann");
+    println!(
+        "        --strategies <A,B,...>   Up to {} strategies (default: always-cooperate,",
+        spatial::MAX_STRATEGIES
+    );
+    println!("                                 always-defect)");
+    println!("        --mix <N,N,...>          Starting share of each strategy (default: equal)");
+    println!(
+        "        --generations <N>        Generations to run (default: {})",
+        spatial::DEFAULT_GENERATIONS
+    );
+    println!(
+        "        --rounds <N>             Rounds per encounter (default: {})",
+        spatial::DEFAULT_ROUNDS
+    );
+    println!("        --seed <N>               Reproduce an earlier run");
+    println!("    {}   Erase your statistics", "reset-stats".cyan());
+    println!();
+    println!("{}", "GAMES:".yellow().bold());
+    for game in GameKind::ALL {
+        println!("    {}", game.slug());
+    }
+    println!();
+    println!("{}", "STRATEGIES:".yellow().bold());
+    for strategy in strategy::registry() {
+        println!(
+            "    {:<24} {}",
+            strategy::slug(strategy.name()),
+            strategy.description()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn help_after_a_command() {
+        assert!(matches!(
+            parse(&args("play --rounds 5 --help")),
+            Ok(Command::Help)
+        ));
+        assert!(matches!(parse(&args("tournament -h")), Ok(Command::Help)));
+    }
+
+    #[test]
+    fn help_as_an_option_value_is_not_help() {
+        let error = parse(&args("play --difficulty -h")).err().unwrap();
+        assert!(error.contains("unknown strategy '-h'"));
+        assert!(matches!(
+            parse(&args("join --address --help")),
+            Ok(Command::Join { .. })
+        ));
+    }
+
+    #[test]
+    fn take_globals_finds_them_before_and_after_the_command() {
+        let mut line = args("--profile Ann play --data-dir /tmp/games --rounds 5");
+        let globals = take_globals(&mut line).unwrap();
+        assert_eq!(globals.profile.as_deref(), Some("Ann"));
+        assert_eq!(globals.data_dir, Some(PathBuf::from("/tmp/games")));
+        assert_eq!(line, args("play --rounds 5"));
+    }
+
+    #[test]
+    fn take_globals_leaves_option_values_alone() {
+        let mut line = args("join --address --profile");
+        let globals = take_globals(&mut line).unwrap();
+        assert!(globals.profile.is_none());
+        assert_eq!(line, args("join --address --profile"));
+    }
+
+    #[test]
+    fn take_globals_needs_a_value() {
+        assert!(take_globals(&mut args("play --profile")).is_err());
+    }
+
+    #[test]
+    fn population_is_only_for_moran() {
+        assert!(parse(&args("evolve --population 50")).is_err());
+        assert!(matches!(
+            parse(&args("evolve --dynamics moran --population 50")),
+            Ok(Command::Evolve { population: 50, .. })
+        ));
+    }
+}
@@ -0,0 +1,438 @@
+use crate::chart;
+use crate::payoff::PayoffMatrix;
+use crate::strategy::{self, Strategy};
+use crate::tournament;
+use colored::Colorize;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub const DEFAULT_GENERATIONS: u32 = 100;
+pub const DEFAULT_POPULATION: u32 = 100;
+pub const DEFAULT_ROUNDS: u32 = 50;
+const MATCHES_PER_PAIRING: u32 = 3;
+/// A strategy whose share falls below this is considered extinct.
+const EXTINCT: f64 = 0.001;
+const MAX_PRINTED_GENERATIONS: u32 = 40;
+const CHARTED_STRATEGIES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dynamics {
+    /// Shares grow in proportion to how well a strategy does against the
+    /// current mix, as in an infinite population.
+    Replicator,
+    /// A finite population where one individual reproduces, chosen by fitness,
+    /// and a random one dies, `population` times per generation.
+    Moran,
+}
+
+impl Dynamics {
+    pub const ALL: [Dynamics; 2] = [Dynamics::Replicator, Dynamics::Moran];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Dynamics::Replicator => "Replicator dynamics",
+            Dynamics::Moran => "Moran process",
+        }
+    }
+
+    pub fn slug(self) -> &'static str {
+        match self {
+            Dynamics::Replicator => "replicator",
+            Dynamics::Moran => "moran",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Dynamics> {
+        Dynamics::ALL.into_iter().find(|d| d.slug() == slug)
+    }
+}
+
+pub struct Settings {
+    pub dynamics: Dynamics,
+    /// Names of the competing strategies; every registered one if empty.
+    pub strategies: Vec<String>,
+    pub generations: u32,
+    /// Individuals in a Moran population. Replicator dynamics only tracks shares.
+    pub population: u32,
+    /// Chance that an offspring is a random strategy instead of its parent's.
+    pub mutation: f64,
+    pub rounds: u32,
+    pub payoffs: PayoffMatrix,
+    pub noise: f64,
+    pub seed: u64,
+}
+
+pub enum Event {
+    Extinct,
+    /// Came back from extinction through mutation.
+    Returned,
+    Fixated,
+}
+
+pub struct EvolutionResult {
+    pub names: Vec<String>,
+    pub dynamics: Dynamics,
+    /// Population shares at the start and after every generation.
+    pub shares: Vec<Vec<f64>>,
+    /// `(generation, strategy, event)` in the order they happened.
+    pub events: Vec<(usize, usize, Event)>,
+    pub population: u32,
+    pub mutation: f64,
+    pub rounds: u32,
+    pub seed: u64,
+}
+
+impl EvolutionResult {
+    /// The generation in which `strategy` last went extinct, if it is gone.
+    fn extinct_since(&self, strategy: usize) -> Option<usize> {
+        match self.events.iter().rev().find(|(_, s, _)| *s == strategy) {
+            Some((generation, _, Event::Extinct)) => Some(*generation),
+            _ => None,
+        }
+    }
+}
+
+/// Average points per round earned by the row strategy against the column one.
+fn payoff_table(names: &[String], settings: &Settings, seeds: &mut ChaCha8Rng) -> Vec<Vec<f64>> {
+    let count = names.len();
+    let mut table = vec![vec![0.0; count]; count];
+    let rounds_played = (settings.rounds * MATCHES_PER_PAIRING) as f64;
+    let instances = || -> Vec<Box<dyn Strategy>> {
+        names
+            .iter()
+            .map(|n| strategy::find(n).expect("validated strategy name"))
+            .collect()
+    };
+    let mut strategies = instances();
+    let mut twins = instances();
+
+    let pairs = (0..count).flat_map(|i| (i..count).map(move |j| (i, j)));
+    for (i, j) in pairs {
+        for _ in 0..MATCHES_PER_PAIRING {
+            let (a, b) = tournament::seats(&mut strategies, &mut twins, i, j);
+            let state = tournament::play_match(
+                a,
+                b,
+                settings.rounds,
+                settings.payoffs,
+                settings.noise,
+                seeds.gen(),
+            );
+            if i == j {
+                table[i][i] += (state.player_score + state.computer_score) as f64 / 2.0;
+            } else {
+                table[i][j] += state.player_score as f64;
+                table[j][i] += state.computer_score as f64;
+            }
+        }
+    }
+
+    for row in &mut table {
+        for points in row.iter_mut() {
+            *points /= rounds_played;
+        }
+    }
+    table
+}
+
+fn replicator_generation(shares: &mut [f64], table: &[Vec<f64>], base: f64, mutation: f64) {
+    let count = shares.len();
+    let fitness: Vec<f64> = (0..count)
+        .map(|i| (0..count).map(|j| shares[j] * table[i][j]).sum::<f64>() - base)
+        .collect();
+    let mean: f64 = shares.iter().zip(&fitness).map(|(s, f)| s * f).sum();
+
+    for (share, fitness) in shares.iter_mut().zip(&fitness) {
+        if mean > 0.0 {
+            *share *= fitness / mean;
+        }
+        *share = (1.0 - mutation) * *share + mutation / count as f64;
+        // Without mutation nothing can come back, so let small shares die out.
+        if mutation == 0.0 && *share < EXTINCT {
+            *share = 0.0;
+        }
+    }
+
+    let total: f64 = shares.iter().sum();
+    for share in shares.iter_mut() {
+        *share /= total;
+    }
+}
+
+/// Picks an index with probability proportional to its weight.
+pub fn pick(weights: &[f64], rng: &mut ChaCha8Rng) -> usize {
+    let total: f64 = weights.iter().sum();
+    let mut target = rng.gen::<f64>() * total;
+    for (i, &weight) in weights.iter().enumerate() {
+        if target < weight {
+            return i;
+        }
+        target -= weight;
+    }
+    weights.iter().rposition(|&w| w > 0.0).unwrap_or(0)
+}
+
+fn moran_generation(
+    counts: &mut [u32],
+    table: &[Vec<f64>],
+    base: f64,
+    mutation: f64,
+    rng: &mut ChaCha8Rng,
+) {
+    let count = counts.len();
+    let population: u32 = counts.iter().sum();
+
+    for _ in 0..population {
+        // Everyone plays everyone else in the population, but not themselves.
+        let weights: Vec<f64> = (0..count)
+            .map(|i| {
+                if counts[i] == 0 {
+                    return 0.0;
+                }
+                let points: f64 = (0..count)
+                    .map(|j| counts[j] as f64 * table[i][j])
+                    .sum::<f64>()
+                    - table[i][i];
+                counts[i] as f64 * (points / (population - 1) as f64 - base)
+            })
+            .collect();
+
+        let parent = pick(&weights, rng);
+        let child = if mutation > 0.0 && rng.gen_bool(mutation) {
+            rng.gen_range(0..count)
+        } else {
+            parent
+        };
+        let sizes: Vec<f64> = counts.iter().map(|&c| c as f64).collect();
+        let victim = pick(&sizes, rng);
+
+        counts[victim] -= 1;
+        counts[child] += 1;
+    }
+}
+
+pub fn run(settings: &Settings) -> EvolutionResult {
+    let names: Vec<String> = if settings.strategies.is_empty() {
+        strategy::registry()
+            .iter()
+            .map(|s| s.name().to_string())
+            .collect()
+    } else {
+        settings.strategies.clone()
+    };
+    let count = names.len();
+
+    let mut rng = ChaCha8Rng::seed_from_u64(settings.seed);
+    let table = payoff_table(&names, settings, &mut rng);
+    // Fitness is measured above a floor just under the worst payoff, so every
+    // strategy keeps a positive chance to reproduce whatever the matrix.
+    let base = table
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::INFINITY, f64::min)
+        - 1.0;
+
+    let mut counts: Vec<u32> = (0..count as u32)
+        .map(|i| {
+            settings.population / count as u32 + u32::from(i < settings.population % count as u32)
+        })
+        .collect();
+    let mut shares: Vec<f64> = match settings.dynamics {
+        Dynamics::Replicator => vec![1.0 / count as f64; count],
+        Dynamics::Moran => counts
+            .iter()
+            .map(|&c| c as f64 / settings.population as f64)
+            .collect(),
+    };
+
+    let mut history = vec![shares.clone()];
+    let mut events = Vec::new();
+    for generation in 1..=settings.generations as usize {
+        match settings.dynamics {
+            Dynamics::Replicator => {
+                replicator_generation(&mut shares, &table, base, settings.mutation)
+            }
+            Dynamics::Moran => {
+                moran_generation(&mut counts, &table, base, settings.mutation, &mut rng);
+                for (share, &c) in shares.iter_mut().zip(&counts) {
+                    *share = c as f64 / settings.population as f64;
+                }
+            }
+        }
+
+        let before = &history[generation - 1];
+        for (strategy, (&was, &now)) in before.iter().zip(&shares).enumerate() {
+            if was >= EXTINCT && now < EXTINCT {
+                events.push((generation, strategy, Event::Extinct));
+            } else if was < EXTINCT && now >= EXTINCT {
+                events.push((generation, strategy, Event::Returned));
+            }
+            if was <= 1.0 - EXTINCT && now > 1.0 - EXTINCT {
+                events.push((generation, strategy, Event::Fixated));
+            }
+        }
+        history.push(shares.clone());
+
+        // Without mutation a population made of one strategy never changes again.
+        let fixated = shares.iter().any(|&s| s > 1.0 - EXTINCT);
+        if fixated && settings.mutation == 0.0 {
+            break;
+        }
+    }
+
+    EvolutionResult {
+        names,
+        dynamics: settings.dynamics,
+        shares: history,
+        events,
+        population: settings.population,
+        mutation: settings.mutation,
+        rounds: settings.rounds,
+        seed: settings.seed,
+    }
+}
+
+fn print_generation(result: &EvolutionResult, generation: usize) {
+    let shares = &result.shares[generation];
+    let mut order: Vec<usize> = (0..shares.len())
+        .filter(|&i| shares[i] >= EXTINCT)
+        .collect();
+    order.sort_by(|&a, &b| shares[b].total_cmp(&shares[a]));
+
+    let leaders: Vec<String> = order
+        .iter()
+        .take(3)
+        .map(|&i| format!("{} {:.1}%", result.names[i], shares[i] * 100.0))
+        .collect();
+    let others = if order.len() > 3 {
+        format!(" (+{} more)", order.len() - 3).bright_black()
+    } else {
+        "".normal()
+    };
+    println!(
+        "  {}  {}{}",
+        format!("Gen {:>4}", generation).cyan(),
+        leaders.join(", "),
+        others
+    );
+}
+
+fn print_events(result: &EvolutionResult, generation: usize) {
+    for (_, strategy, event) in result.events.iter().filter(|(g, _, _)| *g == generation) {
+        let name = &result.names[*strategy];
+        let line = match event {
+            Event::Extinct => format!("  [x] {} went extinct", name).red(),
+            Event::Returned => format!("  [+] {} reappeared through mutation", name).yellow(),
+            Event::Fixated => format!("  [*] {} took over the whole population", name)
+                .bright_green()
+                .bold(),
+        };
+        println!("{}", line);
+    }
+}
+
+/// Charts the share of the strategies that did best at their peak; the rest
+/// would only crowd the bottom of the chart.
+fn print_share_chart(result: &EvolutionResult) {
+    let peak = |i: usize| result.shares.iter().map(|s| s[i]).fold(0.0, f64::max);
+    let mut order: Vec<usize> = (0..result.names.len()).collect();
+    order.sort_by(|&a, &b| peak(b).total_cmp(&peak(a)));
+    order.truncate(CHARTED_STRATEGIES);
+
+    let series: Vec<chart::Series> = order
+        .iter()
+        .zip(chart::PALETTE)
+        .map(|(&i, color)| chart::Series {
+            label: result.names[i].clone(),
+            values: result.shares.iter().map(|s| s[i] * 100.0).collect(),
+            color,
+        })
+        .collect();
+    chart::print_line_chart(&series, 12, &|value| format!("{:.0}%", value));
+    println!(
+        "  {}",
+        format!("generation 0 to {}", result.shares.len() - 1).bright_black()
+    );
+}
+
+pub fn print_results(result: &EvolutionResult) {
+    let last = result.shares.len() - 1;
+    let step = (last as u32).div_ceil(MAX_PRINTED_GENERATIONS).max(1) as usize;
+
+    println!("{}", "═".repeat(60).bright_black());
+    println!(
+        "{}",
+        format!("EVOLUTION - {}", result.dynamics.name())
+            .yellow()
+            .bold()
+    );
+    println!("{}", "═".repeat(60).bright_black());
+    for generation in 0..=last {
+        let has_events = result.events.iter().any(|(g, _, _)| *g == generation);
+        if generation % step == 0 || generation == last || has_events {
+            print_generation(result, generation);
+        }
+        print_events(result, generation);
+    }
+    println!();
+
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "POPULATION SHARE OVER TIME".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    print_share_chart(result);
+    println!();
+
+    let shares = &result.shares[last];
+    let mut order: Vec<usize> = (0..result.names.len()).collect();
+    order.sort_by(|&a, &b| shares[b].total_cmp(&shares[a]));
+    let name_width = result.names.iter().map(|n| n.len()).max().unwrap_or(0);
+
+    println!("{}", "═".repeat(60).bright_black());
+    println!(
+        "{}",
+        format!("FINAL POPULATION (generation {})", last)
+            .yellow()
+            .bold()
+    );
+    println!("{}", "═".repeat(60).bright_black());
+    for i in order {
+        let filled = (shares[i] * 20.0).round() as usize;
+        let bar = format!(
+            "{}{}",
+            "█".repeat(filled).green(),
+            "░".repeat(20 - filled).dimmed()
+        );
+        let status = match result.extinct_since(i) {
+            Some(generation) => format!("extinct since gen {}", generation).red(),
+            None if shares[i] > 1.0 - EXTINCT => "fixated".bright_green().bold(),
+            None => "".normal(),
+        };
+        println!(
+            "  {:<width$}  {} {:>5.1}%  {}",
+            result.names[i],
+            bar,
+            shares[i] * 100.0,
+            status,
+            width = name_width
+        );
+    }
+    println!();
+
+    let population = match result.dynamics {
+        Dynamics::Replicator => String::new(),
+        Dynamics::Moran => format!("population {}, ", result.population),
+    };
+    println!(
+        "{}",
+        format!(
+            "{} strategies, {}{:.1}% mutation, {} rounds per match, seed {}",
+            result.names.len(),
+            population,
+            result.mutation * 100.0,
+            result.rounds,
+            result.seed
+        )
+        .bright_black()
+    );
+}
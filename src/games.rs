@@ -0,0 +1,161 @@
+use crate::payoff::PayoffMatrix;
+use crate::Move;
+use serde::{Deserialize, Serialize};
+
+/// A symmetric 2x2 game. `Move::Cooperate` and `Move::Defect` stand for the
+/// game's first and second option; only the labels and payoffs change.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GameKind {
+    PrisonersDilemma,
+    StagHunt,
+    Chicken,
+    Deadlock,
+    Harmony,
+    BattleOfTheSexes,
+}
+
+impl GameKind {
+    pub const ALL: [GameKind; 6] = [
+        GameKind::PrisonersDilemma,
+        GameKind::StagHunt,
+        GameKind::Chicken,
+        GameKind::Deadlock,
+        GameKind::Harmony,
+        GameKind::BattleOfTheSexes,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameKind::PrisonersDilemma => "Prisoner's Dilemma",
+            GameKind::StagHunt => "Stag Hunt",
+            GameKind::Chicken => "Chicken (Snowdrift)",
+            GameKind::Deadlock => "Deadlock",
+            GameKind::Harmony => "Harmony",
+            GameKind::BattleOfTheSexes => "Battle of the Sexes",
+        }
+    }
+
+    pub fn slug(self) -> &'static str {
+        match self {
+            GameKind::PrisonersDilemma => "prisoners-dilemma",
+            GameKind::StagHunt => "stag-hunt",
+            GameKind::Chicken => "chicken",
+            GameKind::Deadlock => "deadlock",
+            GameKind::Harmony => "harmony",
+            GameKind::BattleOfTheSexes => "battle-of-the-sexes",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<GameKind> {
+        GameKind::ALL
+            .iter()
+            .copied()
+            .find(|game| game.slug().eq_ignore_ascii_case(slug))
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            GameKind::PrisonersDilemma => "Betrayal pays, unless everyone does it",
+            GameKind::StagHunt => "Trust pays off only if your partner trusts you too",
+            GameKind::Chicken => "Someone has to back down before the crash",
+            GameKind::Deadlock => "Both sides prefer conflict to cooperation",
+            GameKind::Harmony => "Cooperating is simply the best move",
+            GameKind::BattleOfTheSexes => "Agree on where to go, but each prefers their own pick",
+        }
+    }
+
+    pub fn move_label(self, player_move: Move) -> &'static str {
+        match (self, player_move) {
+            (GameKind::PrisonersDilemma, Move::Cooperate) => "Cooperate",
+            (GameKind::PrisonersDilemma, Move::Defect) => "Defect",
+            (GameKind::StagHunt, Move::Cooperate) => "Stag",
+            (GameKind::StagHunt, Move::Defect) => "Hare",
+            (GameKind::Chicken, Move::Cooperate) => "Swerve",
+            (GameKind::Chicken, Move::Defect) => "Straight",
+            (GameKind::Deadlock, Move::Cooperate) => "Disarm",
+            (GameKind::Deadlock, Move::Defect) => "Arm",
+            (GameKind::Harmony, Move::Cooperate) => "Share",
+            (GameKind::Harmony, Move::Defect) => "Hoard",
+            (GameKind::BattleOfTheSexes, Move::Cooperate) => "Yield",
+            (GameKind::BattleOfTheSexes, Move::Defect) => "Insist",
+        }
+    }
+
+    pub fn move_hint(self, player_move: Move) -> &'static str {
+        match (self, player_move) {
+            (GameKind::PrisonersDilemma, Move::Cooperate) => {
+                "Trust and work together for mutual benefit"
+            }
+            (GameKind::PrisonersDilemma, Move::Defect) => "Act in self-interest and betray",
+            (GameKind::StagHunt, Move::Cooperate) => {
+                "Hunt the stag together - a feast, but only if your partner joins"
+            }
+            (GameKind::StagHunt, Move::Defect) => "Catch a hare alone - smaller, but guaranteed",
+            (GameKind::Chicken, Move::Cooperate) => "Swerve away - lose face, but stay safe",
+            (GameKind::Chicken, Move::Defect) => {
+                "Drive straight - win big if they swerve, crash if they don't"
+            }
+            (GameKind::Deadlock, Move::Cooperate) => "Lay down your arms and hope for goodwill",
+            (GameKind::Deadlock, Move::Defect) => "Stay armed - the choice both sides prefer",
+            (GameKind::Harmony, Move::Cooperate) => "Share the resource - best for everyone",
+            (GameKind::Harmony, Move::Defect) => "Keep it to yourself - gains nothing but spite",
+            (GameKind::BattleOfTheSexes, Move::Cooperate) => "Go to your partner's favourite event",
+            (GameKind::BattleOfTheSexes, Move::Defect) => "Go to your own favourite event",
+        }
+    }
+
+    /// The game's own payoff table. The Prisoner's Dilemma can be customised,
+    /// so this is only its classic default.
+    pub fn payoffs(self) -> PayoffMatrix {
+        let (temptation, reward, punishment, sucker) = match self {
+            GameKind::PrisonersDilemma => return PayoffMatrix::CLASSIC,
+            GameKind::StagHunt => (3, 4, 2, 0),
+            GameKind::Chicken => (4, 3, 0, 1),
+            GameKind::Deadlock => (3, 1, 2, 0),
+            GameKind::Harmony => (3, 4, 1, 2),
+            GameKind::BattleOfTheSexes => (3, 0, 0, 2),
+        };
+        PayoffMatrix {
+            temptation,
+            reward,
+            punishment,
+            sucker,
+        }
+    }
+
+    pub fn tips(self) -> &'static [&'static str] {
+        match self {
+            GameKind::PrisonersDilemma => &[
+                "+ Cooperate for steady gains but risk being exploited",
+                "- Defect for short-term advantage but risk mutual punishment",
+                "* Pay attention to opponent patterns and adapt",
+                "^ Mix strategies to keep opponent guessing",
+            ],
+            GameKind::StagHunt => &[
+                "+ Stag/Stag is the best outcome for both of you",
+                "- Hare is safe: it never scores less than 2",
+                "* Both outcomes where you match are stable - trust decides which one you reach",
+            ],
+            GameKind::Chicken => &[
+                "+ Going straight wins big against a swerver",
+                "- Two straight drivers crash and both get nothing",
+                "* Convince your opponent you will never swerve",
+            ],
+            GameKind::Deadlock => &[
+                "+ Arming is best no matter what the other side does",
+                "- Mutual disarmament is worse for both than mutual arming",
+                "* There is no dilemma here - and no reason to trust",
+            ],
+            GameKind::Harmony => &[
+                "+ Sharing beats hoarding whatever the other player does",
+                "- Hoarding only hurts both of you",
+                "* Self-interest and the common good point the same way",
+            ],
+            GameKind::BattleOfTheSexes => &[
+                "+ Insisting wins when your partner yields",
+                "- If you both insist, or both yield, you end up apart with nothing",
+                "* Take turns to share the good evenings",
+            ],
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::games::GameKind;
+use crate::payoff::PayoffMatrix;
+use crate::profile;
+use crate::storage;
+use crate::{warn, GameState, Move};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HISTORY_FILE: &str = "game_history.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub player_move: Move,
+    pub computer_move: Move,
+    pub player_points: i32,
+    pub computer_points: i32,
+    /// The moves chosen before noise, kept only when noise flipped one of them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intended: Option<(Move, Move)>,
+}
+
+impl RoundRecord {
+    /// What the player and the computer meant to play this round.
+    pub fn intended(&self) -> (Move, Move) {
+        self.intended.unwrap_or((self.player_move, self.computer_move))
+    }
+}
+
+/// Everything needed to inspect, or rebuild, a finished game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameRecord {
+    /// Seconds since the Unix epoch when the game finished.
+    pub timestamp: u64,
+    pub game: GameKind,
+    pub opponent: String,
+    pub payoffs: PayoffMatrix,
+    #[serde(default)]
+    pub noise: f64,
+    #[serde(default)]
+    pub continuation: Option<f64>,
+    pub seed: u64,
+    pub rounds: Vec<RoundRecord>,
+    pub player_score: i32,
+    pub computer_score: i32,
+}
+
+impl GameRecord {
+    pub fn from_state(state: &GameState) -> Self {
+        GameRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            game: state.game,
+            opponent: state.opponent.clone(),
+            payoffs: state.payoffs,
+            noise: state.noise,
+            continuation: state.continuation,
+            seed: state.seed,
+            rounds: state
+                .history
+                .iter()
+                .enumerate()
+                .map(|(round, &(player_move, computer_move))| {
+                    let (player_points, computer_points) =
+                        state.calculate_payoff(player_move, computer_move);
+                    RoundRecord {
+                        player_move,
+                        computer_move,
+                        player_points,
+                        computer_points,
+                        intended: state
+                            .intended
+                            .get(round)
+                            .copied()
+                            .filter(|&intended| intended != (player_move, computer_move)),
+                    }
+                })
+                .collect(),
+            player_score: state.player_score,
+            computer_score: state.computer_score,
+        }
+    }
+
+    /// A finished `GameState` equivalent to the recorded game.
+    pub fn to_state(&self) -> GameState {
+        let mut state = GameState::new(
+            self.rounds.len() as u32,
+            &self.opponent,
+            self.game,
+            self.payoffs,
+            self.noise,
+            self.seed,
+        );
+        state.round = self.rounds.len() as u32;
+        state.continuation = self.continuation;
+        state.history = self
+            .rounds
+            .iter()
+            .map(|r| (r.player_move, r.computer_move))
+            .collect();
+        state.intended = self.rounds.iter().map(RoundRecord::intended).collect();
+        state.player_score = self.player_score;
+        state.computer_score = self.computer_score;
+        state
+    }
+
+    pub fn result_label(&self) -> &'static str {
+        if self.player_score > self.computer_score {
+            "WIN"
+        } else if self.player_score < self.computer_score {
+            "LOSS"
+        } else {
+            "TIE"
+        }
+    }
+
+    /// UTC date and time the game finished, as `YYYY-MM-DD HH:MM`.
+    pub fn date(&self) -> String {
+        let days = (self.timestamp / 86_400) as i64;
+        let minutes_of_day = (self.timestamp % 86_400) / 60;
+
+        // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm).
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            year,
+            month,
+            day,
+            minutes_of_day / 60,
+            minutes_of_day % 60
+        )
+    }
+}
+
+pub fn load() -> Result<Vec<GameRecord>, String> {
+    load_from(&profile::data_path(HISTORY_FILE))
+}
+
+/// Another profile's games, e.g. for the leaderboard.
+pub fn load_for(name: &str) -> Result<Vec<GameRecord>, String> {
+    load_from(&profile::data_path_for(name, HISTORY_FILE))
+}
+
+fn load_from(path: &Path) -> Result<Vec<GameRecord>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("the file is damaged ({})", e))
+}
+
+/// Adds a finished game. A history that can't be read is kept as `.corrupt`
+/// and a new one is started, rather than being overwritten.
+pub fn append(record: GameRecord) -> Result<(), String> {
+    let path = profile::data_path(HISTORY_FILE);
+    let mut records = match load_from(&path) {
+        Ok(records) => records,
+        Err(error) => {
+            let kept = storage::sibling(&path, "corrupt");
+            fs::copy(&path, &kept)
+                .map_err(|e| format!("could not write {}: {}", kept.display(), e))?;
+            warn(&format!(
+                "Could not load {}: {}. It was kept as {} and a new history was started.",
+                path.display(),
+                error,
+                kept.display()
+            ));
+            Vec::new()
+        }
+    };
+    records.push(record);
+    let json = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    storage::write_atomic(&path, &json)
+        .map_err(|e| format!("could not write {}: {}", path.display(), e))
+}
@@ -0,0 +1,145 @@
+use crate::games::GameKind;
+use crate::{
+    animate_round_result, choose_game_and_payoffs, clear_screen, display_game_summary,
+    get_player_move, print_game_state, print_payoff_matrix, print_title, profile, read_length,
+    GameState, Length, Move,
+};
+use colored::Colorize;
+use std::io::{self, Write};
+
+fn wait_for_enter(prompt: &str) {
+    print!("{}: ", prompt.cyan());
+    io::stdout().flush().unwrap();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+/// Asks for a player's name, falling back to `default`. A name already taken
+/// by the other player is refused, since the screens would be ambiguous.
+pub fn read_name(prompt: &str, default: &str, taken: Option<&str>) -> String {
+    loop {
+        print!(
+            "{}: ",
+            format!("{} (Enter for {})", prompt, default).cyan().bold()
+        );
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        let name = match input.trim() {
+            "" => default,
+            name => name,
+        };
+
+        if name.chars().count() > profile::MAX_NAME_LENGTH {
+            println!(
+                "{}",
+                format!(
+                    "[!] Names can be at most {} characters.",
+                    profile::MAX_NAME_LENGTH
+                )
+                .red()
+            );
+        } else if taken.is_some_and(|taken| taken.eq_ignore_ascii_case(name)) {
+            println!("{}", "[!] The players need different names.".red());
+        } else {
+            return name.to_string();
+        }
+    }
+}
+
+/// Lets `name` choose a move, then clears the screen before anyone else looks.
+fn take_turn(state: &GameState, name: &str, other: &str, then: &str) -> Move {
+    print_title();
+    println!(
+        "{}",
+        format!("{}'S TURN", name.to_uppercase()).yellow().bold()
+    );
+    println!("{}", format!("{}, no peeking!", other).bright_black());
+    print_game_state(state);
+
+    let choice = get_player_move(state.game, false).expect("hotseat games can't be saved");
+    clear_screen();
+    println!();
+    println!("{}", "Move locked in.".bright_green().bold());
+    println!();
+    wait_for_enter(then);
+    choice
+}
+
+/// A game between two people sharing the terminal. They enter their moves one
+/// after the other, and neither gets to see what the other picked until the
+/// round is resolved. Anything left as `None` is asked for.
+pub fn run(game: Option<GameKind>, length: Option<Length>) {
+    print_title();
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "HOTSEAT".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    println!();
+    println!(
+        "{}",
+        "Two players, one keyboard. The screen is cleared after every move.".bright_black()
+    );
+    println!();
+    let first = read_name("Player 1 name", &profile::active(), None);
+    let second = read_name("Player 2 name", "Player 2", Some(&first));
+
+    print_title();
+    let (game, payoffs) = choose_game_and_payoffs(game);
+    print_title();
+    print_payoff_matrix(&payoffs, game);
+
+    // Without a computer strategy the seed only decides when an open-ended
+    // game stops.
+    let seed = rand::random();
+    let mut state = match length.unwrap_or_else(read_length) {
+        Length::Rounds(rounds) => GameState::new(rounds, &second, game, payoffs, 0.0, seed),
+        Length::Continuation(continuation) => {
+            GameState::open_ended(continuation, &second, game, payoffs, 0.0, seed)
+        }
+    };
+    state.player = Some(first.clone());
+
+    while state.round < state.total_rounds {
+        state.round += 1;
+        let first_move = take_turn(
+            &state,
+            &first,
+            &second,
+            &format!("Pass the keyboard to {} and press Enter", second),
+        );
+        let second_move = take_turn(
+            &state,
+            &second,
+            &first,
+            "Call everyone back and press Enter to see the result",
+        );
+        let (first_points, second_points) = state.calculate_payoff(first_move, second_move);
+
+        print_title();
+        print_game_state(&state);
+        animate_round_result(
+            state.game,
+            state.players(),
+            (first_move, second_move),
+            first_move,
+            second_move,
+            first_points,
+            second_points,
+        );
+
+        state.player_score += first_points;
+        state.computer_score += second_points;
+        state.history.push((first_move, second_move));
+        state.intended.push((first_move, second_move));
+        state.draw_continuation();
+
+        println!();
+        wait_for_enter("Press Enter to continue");
+    }
+
+    display_game_summary(&state, None);
+    println!("{}", "═".repeat(60).bright_black());
+    wait_for_enter("Press Enter to return to menu");
+}
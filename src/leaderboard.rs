@@ -0,0 +1,164 @@
+use crate::{print_title, profile, strategy, Statistics};
+use colored::Colorize;
+use std::cmp::Reverse;
+use std::io::{self, Write};
+
+/// Profiles with fewer games than this are left out of the win-rate ranking.
+pub const MIN_GAMES_FOR_WIN_RATE: u32 = 5;
+const TOP: usize = 10;
+
+fn print_ranking(title: &str, rows: &[(String, String)]) {
+    let active = profile::active();
+
+    println!("{}", title.yellow().bold());
+    if rows.is_empty() {
+        println!("  {}", "Nobody qualifies yet.".bright_black());
+    }
+    for (rank, (name, value)) in rows.iter().take(TOP).enumerate() {
+        let line = format!("{:>3}. {:<22} {}", rank + 1, name, value);
+        let line = match rank {
+            0 => line.bright_yellow().bold(),
+            1 => line.white().bold(),
+            2 => line.yellow(),
+            _ => line.normal(),
+        };
+        if *name == active {
+            println!("  {} {}", line, "(you)".bright_cyan());
+        } else {
+            println!("  {}", line);
+        }
+    }
+    println!();
+}
+
+pub fn display() {
+    let players: Vec<(String, Statistics)> = profile::names()
+        .into_iter()
+        .map(|name| {
+            let stats = Statistics::load_for(&name);
+            (name, stats)
+        })
+        .filter(|(_, stats)| stats.games_played > 0)
+        .collect();
+
+    print_title();
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "LEADERBOARD".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    println!();
+
+    if players.is_empty() {
+        println!("{}", "No profile has finished a game yet.".yellow());
+    } else {
+        let mut by_win_rate: Vec<&(String, Statistics)> = players
+            .iter()
+            .filter(|(_, stats)| stats.games_played >= MIN_GAMES_FOR_WIN_RATE)
+            .collect();
+        by_win_rate.sort_by(|(_, a), (_, b)| {
+            b.win_rate()
+                .total_cmp(&a.win_rate())
+                .then(b.games_played.cmp(&a.games_played))
+        });
+        print_ranking(
+            &format!("WIN RATE (at least {} games)", MIN_GAMES_FOR_WIN_RATE),
+            &by_win_rate
+                .iter()
+                .map(|(name, stats)| {
+                    (
+                        name.clone(),
+                        format!("{:.1}% ({} games)", stats.win_rate(), stats.games_played),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let mut by_points: Vec<&(String, Statistics)> = players.iter().collect();
+        by_points.sort_by_key(|(_, stats)| Reverse(stats.total_points));
+        print_ranking(
+            "TOTAL POINTS",
+            &by_points
+                .iter()
+                .map(|(name, stats)| (name.clone(), stats.total_points.to_string()))
+                .collect::<Vec<_>>(),
+        );
+
+        let mut by_differential: Vec<&(String, Statistics)> = players.iter().collect();
+        by_differential.sort_by_key(|(_, stats)| Reverse(stats.best_score_differential));
+        print_ranking(
+            "BEST SCORE DIFFERENTIAL",
+            &by_differential
+                .iter()
+                .map(|(name, stats)| (name.clone(), format!("{:+}", stats.best_score_differential)))
+                .collect::<Vec<_>>(),
+        );
+
+        print_opponent_records(&players);
+    }
+
+    println!("{}", "═".repeat(60).bright_black());
+    print!("{}: ", "Press Enter to return to menu".cyan());
+    io::stdout().flush().unwrap();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+/// The best record against each opponent, in menu order.
+fn print_opponent_records(players: &[(String, Statistics)]) {
+    let mut opponents: Vec<String> = strategy::registry()
+        .iter()
+        .map(|s| s.name().to_string())
+        .collect();
+    for (_, stats) in players {
+        for record in &stats.by_opponent {
+            if !opponents.contains(&record.opponent) {
+                opponents.push(record.opponent.clone());
+            }
+        }
+    }
+
+    println!("{}", "BEST RECORD PER OPPONENT".yellow().bold());
+    println!(
+        "  {}",
+        format!(
+            "{:<24} {:<22} {:>11} {:>7}",
+            "Opponent", "Leader", "W-L-T", "Win %"
+        )
+        .cyan()
+        .bold()
+    );
+
+    let mut any = false;
+    for opponent in &opponents {
+        let best = players
+            .iter()
+            .filter_map(|(name, stats)| {
+                stats
+                    .by_opponent
+                    .iter()
+                    .find(|record| record.opponent == *opponent)
+                    .map(|record| (name, record))
+            })
+            .max_by(|(_, a), (_, b)| {
+                a.win_rate()
+                    .total_cmp(&b.win_rate())
+                    .then(a.games_played.cmp(&b.games_played))
+            });
+
+        if let Some((name, record)) = best {
+            any = true;
+            println!(
+                "  {:<24} {:<22} {:>11} {:>6.1}%",
+                opponent,
+                name,
+                format!(
+                    "{}-{}-{}",
+                    record.games_won, record.games_lost, record.games_tied
+                ),
+                record.win_rate()
+            );
+        }
+    }
+    if !any {
+        println!("  {}", "No recorded games yet.".bright_black());
+    }
+    println!();
+}
@@ -1,30 +1,82 @@
+mod analytics;
+mod bot;
+mod chart;
+mod cli;
+mod evolution;
+mod games;
+mod history;
+mod hotseat;
+mod leaderboard;
+mod network;
+mod payoff;
+mod profile;
+mod replay;
+mod save;
+mod spatial;
+mod storage;
+mod strategy;
+mod tournament;
+
+use cli::Command;
 use colored::Colorize;
-use rand::Rng;
+use games::GameKind;
+use history::GameRecord;
+use payoff::PayoffMatrix;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
 use std::thread;
 use std::time::Duration;
+use strategy::Strategy;
 
 const STATS_FILE: &str = "game_stats.json";
+/// Bumped whenever the layout of the stats file changes; see `Statistics::migrate`.
+const STATS_VERSION: u64 = 2;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum Move {
     Cooperate,
     Defect,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Difficulty {
-    Easy,
-    Medium,
-    Hard,
-    Legendary,
+impl Move {
+    fn opposite(self) -> Move {
+        match self {
+            Move::Cooperate => Move::Defect,
+            Move::Defect => Move::Cooperate,
+        }
+    }
+}
+
+/// How long a game against the computer lasts.
+#[derive(Debug, Clone, Copy)]
+enum Length {
+    Rounds(u32),
+    /// After every round another one follows with this probability, so nobody
+    /// knows which round is the last.
+    Continuation(f64),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Statistics {
+    version: u64,
+    games_played: u32,
+    games_won: u32,
+    games_lost: u32,
+    games_tied: u32,
+    total_points: i32,
+    best_score_differential: i32,
+    worst_score_differential: i32,
+    by_opponent: Vec<OpponentStats>,
+}
+
+/// Results against a single difficulty level or classic strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OpponentStats {
+    opponent: String,
     games_played: u32,
     games_won: u32,
     games_lost: u32,
@@ -34,9 +86,56 @@ struct Statistics {
     worst_score_differential: i32,
 }
 
+impl OpponentStats {
+    fn new(opponent: &str) -> Self {
+        OpponentStats {
+            opponent: opponent.to_string(),
+            games_played: 0,
+            games_won: 0,
+            games_lost: 0,
+            games_tied: 0,
+            total_points: 0,
+            best_score_differential: 0,
+            worst_score_differential: 0,
+        }
+    }
+
+    fn record(&mut self, player_score: i32, computer_score: i32) {
+        let score_diff = player_score - computer_score;
+        self.games_played += 1;
+        self.total_points += player_score;
+
+        if player_score > computer_score {
+            self.games_won += 1;
+        } else if player_score < computer_score {
+            self.games_lost += 1;
+        } else {
+            self.games_tied += 1;
+        }
+
+        // The first game sets both, since the starting zeros weren't played.
+        if self.games_played == 1 {
+            self.best_score_differential = score_diff;
+            self.worst_score_differential = score_diff;
+        } else {
+            self.best_score_differential = self.best_score_differential.max(score_diff);
+            self.worst_score_differential = self.worst_score_differential.min(score_diff);
+        }
+    }
+
+    fn win_rate(&self) -> f32 {
+        if self.games_played == 0 {
+            0.0
+        } else {
+            (self.games_won as f32 / self.games_played as f32) * 100.0
+        }
+    }
+}
+
 impl Statistics {
     fn new() -> Self {
         Statistics {
+            version: STATS_VERSION,
             games_played: 0,
             games_won: 0,
             games_lost: 0,
@@ -44,24 +143,191 @@ impl Statistics {
             total_points: 0,
             best_score_differential: 0,
             worst_score_differential: 0,
+            by_opponent: Vec::new(),
         }
     }
 
     fn load() -> Self {
-        if Path::new(STATS_FILE).exists() {
-            if let Ok(content) = fs::read_to_string(STATS_FILE) {
-                if let Ok(stats) = serde_json::from_str(&content) {
-                    return stats;
-                }
+        let mut stats = Statistics::load_from(&profile::data_path(STATS_FILE));
+        if stats.missing_breakdown() {
+            if let Ok(history) = history::load() {
+                stats.backfill_breakdown(&history);
+            }
+        }
+        stats
+    }
+
+    fn load_for(profile: &str) -> Self {
+        let mut stats = Statistics::load_from(&profile::data_path_for(profile, STATS_FILE));
+        if stats.missing_breakdown() {
+            if let Ok(history) = history::load_for(profile) {
+                stats.backfill_breakdown(&history);
+            }
+        }
+        stats
+    }
+
+    /// Stats files written before the per-opponent breakdown only have totals.
+    fn missing_breakdown(&self) -> bool {
+        self.games_played > 0 && self.by_opponent.is_empty()
+    }
+
+    /// Rebuilds the breakdown from the most recent recorded games. Games older
+    /// than the history stay in the totals and show up as untracked.
+    fn backfill_breakdown(&mut self, history: &[GameRecord]) {
+        let start = history.len().saturating_sub(self.games_played as usize);
+        for game in &history[start..] {
+            self.against(&game.opponent)
+                .record(game.player_score, game.computer_score);
+        }
+    }
+
+    fn against(&mut self, opponent: &str) -> &mut OpponentStats {
+        let index = match self.by_opponent.iter().position(|o| o.opponent == opponent) {
+            Some(i) => i,
+            None => {
+                self.by_opponent.push(OpponentStats::new(opponent));
+                self.by_opponent.len() - 1
+            }
+        };
+        &mut self.by_opponent[index]
+    }
+
+    /// The breakdown in menu order, with opponents that are no longer offered last.
+    fn breakdown(&self) -> Vec<&OpponentStats> {
+        let order: Vec<String> = strategy::registry()
+            .iter()
+            .map(|s| s.name().to_string())
+            .collect();
+        let mut breakdown: Vec<&OpponentStats> = self.by_opponent.iter().collect();
+        breakdown.sort_by_key(|o| {
+            order
+                .iter()
+                .position(|name| *name == o.opponent)
+                .unwrap_or(order.len())
+        });
+        breakdown
+    }
+
+    /// Falls back to the backup when the file can't be used, and says so
+    /// instead of quietly starting from zero.
+    fn load_from(path: &Path) -> Self {
+        if !path.exists() {
+            return Statistics::new();
+        }
+        let error = match Statistics::read(path) {
+            Ok(stats) => return stats,
+            Err(error) => error,
+        };
+        warn(&format!("Could not load {}: {}.", path.display(), error));
+
+        match Statistics::read(&storage::sibling(path, "bak")) {
+            Ok(stats) => {
+                warn("Using the backup from before the last save instead.");
+                stats
+            }
+            Err(_) => {
+                warn(&format!(
+                    "No usable backup either; starting from zero. The damaged file will be kept as {}.",
+                    storage::sibling(path, "corrupt").display()
+                ));
+                Statistics::new()
             }
         }
-        Statistics::new()
     }
 
-    fn save(&self) {
-        if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = fs::write(STATS_FILE, json);
+    fn read(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut value: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("the file is damaged ({})", e))?;
+        Statistics::migrate(&mut value)?;
+        serde_json::from_value(value).map_err(|e| format!("the file is damaged ({})", e))
+    }
+
+    /// Upgrades an older layout of the stats file, one version at a time.
+    fn migrate(value: &mut serde_json::Value) -> Result<(), String> {
+        let stats = value
+            .as_object_mut()
+            .ok_or("the file is damaged (expected an object)")?;
+        let mut version = stats.get("version").and_then(|v| v.as_u64()).unwrap_or(1);
+        if version > STATS_VERSION {
+            return Err(format!(
+                "it was written by a newer version of the game (format {})",
+                version
+            ));
+        }
+
+        if version == 1 {
+            // Version 1 only kept the overall totals.
+            stats
+                .entry("by_opponent")
+                .or_insert_with(|| serde_json::json!([]));
+            version = 2;
+        }
+
+        stats.insert("version".to_string(), version.into());
+        Ok(())
+    }
+
+    fn save(&self) -> Result<(), String> {
+        self.save_to(&profile::data_path(STATS_FILE))
+    }
+
+    /// Writes the file atomically. The previous file is kept as `.bak` if it was
+    /// readable, or as `.corrupt` if it wasn't, rather than being overwritten.
+    /// A file from a newer version of the game is left alone altogether.
+    fn save_to(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+
+        if Statistics::file_version(path).is_some_and(|version| version > STATS_VERSION) {
+            return Err(format!(
+                "{} was written by a newer version of the game, which would lose data if it were overwritten",
+                path.display()
+            ));
+        }
+        if path.exists() {
+            let kept = if Statistics::read(path).is_ok() {
+                storage::sibling(path, "bak")
+            } else {
+                storage::sibling(path, "corrupt")
+            };
+            fs::copy(path, &kept)
+                .map_err(|e| format!("could not write {}: {}", kept.display(), e))?;
+        }
+        storage::write_atomic(path, &json)
+            .map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+
+    /// The format version recorded in a stats file, if it can be read at all.
+    fn file_version(path: &Path) -> Option<u64> {
+        let content = fs::read_to_string(path).ok()?;
+        let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+        Some(value.get("version").and_then(|v| v.as_u64()).unwrap_or(1))
+    }
+
+    fn record(&mut self, state: &GameState) {
+        let score_diff = state.player_score - state.computer_score;
+        self.games_played += 1;
+        self.total_points += state.player_score;
+
+        if state.player_score > state.computer_score {
+            self.games_won += 1;
+        } else if state.player_score < state.computer_score {
+            self.games_lost += 1;
+        } else {
+            self.games_tied += 1;
+        }
+
+        if self.games_played == 1 {
+            self.best_score_differential = score_diff;
+            self.worst_score_differential = score_diff;
+        } else {
+            self.best_score_differential = self.best_score_differential.max(score_diff);
+            self.worst_score_differential = self.worst_score_differential.min(score_diff);
         }
+
+        self.against(&state.opponent)
+            .record(state.player_score, state.computer_score);
     }
 
     fn win_rate(&self) -> f32 {
@@ -73,38 +339,136 @@ impl Statistics {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 struct GameState {
     player_score: i32,
     computer_score: i32,
     round: u32,
+    /// In an open-ended game this is only ever one ahead of `round`, and is
+    /// extended each time the game is drawn to continue.
     total_rounds: u32,
+    /// Chance of another round after each one, for games of unknown length.
+    #[serde(default)]
+    continuation: Option<f64>,
+    /// The moves actually played, after noise. This is what strategies see.
     history: Vec<(Move, Move)>,
-    difficulty: Difficulty,
+    /// The moves each side chose before noise was applied.
+    #[serde(default)]
+    intended: Vec<(Move, Move)>,
+    opponent: String,
+    /// In a game between two people, the first player's name; `opponent` then
+    /// names the second. Such games are never saved.
+    #[serde(skip)]
+    player: Option<String>,
+    game: GameKind,
+    payoffs: PayoffMatrix,
+    /// Chance that any chosen move comes out as the opposite one.
+    #[serde(default)]
+    noise: f64,
+    seed: u64,
+    rng: ChaCha8Rng,
 }
 
 impl GameState {
-    fn new(total_rounds: u32, difficulty: Difficulty) -> Self {
+    fn new(
+        total_rounds: u32,
+        opponent: &str,
+        game: GameKind,
+        payoffs: PayoffMatrix,
+        noise: f64,
+        seed: u64,
+    ) -> Self {
         GameState {
             player_score: 0,
             computer_score: 0,
             round: 0,
             total_rounds,
+            continuation: None,
             history: Vec::new(),
-            difficulty,
+            intended: Vec::new(),
+            opponent: opponent.to_string(),
+            player: None,
+            game,
+            payoffs,
+            noise,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
         }
     }
 
-    fn calculate_payoff(&self, player_move: Move, computer_move: Move) -> (i32, i32) {
-        match (player_move, computer_move) {
-            (Move::Cooperate, Move::Cooperate) => (3, 3),
-            (Move::Cooperate, Move::Defect) => (0, 5),
-            (Move::Defect, Move::Cooperate) => (5, 0),
-            (Move::Defect, Move::Defect) => (1, 1),
+    /// The move that actually gets played when `intended` was chosen. Without
+    /// noise the RNG is left alone, so noise-free seeds replay as before.
+    fn tremble(&mut self, intended: Move) -> Move {
+        if self.noise > 0.0 && self.rng.gen_bool(self.noise) {
+            intended.opposite()
+        } else {
+            intended
+        }
+    }
+
+    /// A game whose length is decided round by round with probability `continuation`.
+    fn open_ended(
+        continuation: f64,
+        opponent: &str,
+        game: GameKind,
+        payoffs: PayoffMatrix,
+        noise: f64,
+        seed: u64,
+    ) -> Self {
+        let mut state = GameState::new(1, opponent, game, payoffs, noise, seed);
+        state.continuation = Some(continuation);
+        state
+    }
+
+    /// Both names in a game between two people; `None` against the computer.
+    fn players(&self) -> Option<(&str, &str)> {
+        self.player
+            .as_deref()
+            .map(|player| (player, self.opponent.as_str()))
+    }
+
+    /// After a round of an open-ended game, decides whether another one follows.
+    fn draw_continuation(&mut self) {
+        if let Some(continuation) = self.continuation {
+            if self.rng.gen_bool(continuation) {
+                self.total_rounds = self.round + 1;
+            }
         }
     }
 
+    /// The average number of rounds an open-ended game lasts.
+    fn expected_length(&self) -> Option<f64> {
+        self.continuation.map(|w| 1.0 / (1.0 - w))
+    }
+
+    /// How many of the player's and the computer's moves noise has flipped.
+    fn flips(&self) -> (usize, usize) {
+        self.intended
+            .iter()
+            .zip(&self.history)
+            .fold((0, 0), |(player, computer), (intended, actual)| {
+                (
+                    player + usize::from(intended.0 != actual.0),
+                    computer + usize::from(intended.1 != actual.1),
+                )
+            })
+    }
+
+    fn calculate_payoff(&self, player_move: Move, computer_move: Move) -> (i32, i32) {
+        self.payoffs.payoff(player_move, computer_move)
+    }
+
     fn game_progress_bar(&self) -> String {
+        if let (Some(continuation), Some(expected)) = (self.continuation, self.expected_length())
+        {
+            return format!(
+                "Round {} of ??? - {} chance of another round, about {} expected",
+                self.round.to_string().cyan(),
+                format!("{:.0}%", continuation * 100.0).cyan(),
+                format!("{:.0}", expected).cyan()
+            );
+        }
+
         let filled = (self.round as f32 / self.total_rounds as f32 * 30.0) as usize;
         let empty = 30 - filled;
         let bar = format!(
@@ -148,54 +512,155 @@ fn print_title() {
         "║                                                           ║".bright_cyan()
     );
     println!("{}", "╚═══════════════════════════════════════════════════════════╝".bright_cyan());
+    println!(
+        "  {} {}",
+        "Player:".bright_black(),
+        profile::active().bright_cyan().bold()
+    );
     println!();
 }
 
-fn print_payoff_matrix() {
+/// Reports a problem with the player's files, pausing so it isn't cleared
+/// away by the next screen. Tests don't wait.
+fn warn(message: &str) {
+    eprintln!("{}", format!("[!] {}", message).red());
+    if !cfg!(test) {
+        thread::sleep(Duration::from_millis(2000));
+    }
+}
+
+fn print_payoff_matrix(matrix: &PayoffMatrix, game: GameKind) {
+    let cooperate = game.move_label(Move::Cooperate);
+    let defect = game.move_label(Move::Defect);
+    let is_dilemma = game == GameKind::PrisonersDilemma;
+    let annotation = |text: &str| {
+        if is_dilemma {
+            format!(" ({})", text)
+        } else {
+            String::new()
+        }
+    };
+
     println!("{}", "═".repeat(60).bright_black());
-    println!("{}", "PAYOFF MATRIX (Your Points / Computer Points)".yellow().bold());
+    println!(
+        "{}",
+        format!("{} - PAYOFF MATRIX (Your Points / Computer Points)", game.name().to_uppercase())
+            .yellow()
+            .bold()
+    );
     println!("{}", "═".repeat(60).bright_black());
     println!(
         "  {} {}",
-        "[C] Both Cooperate:".green(),
-        "3 / 3 (Mutual Benefit)".bright_green()
+        format!("[C] Both {}:", cooperate).green(),
+        format!("{} / {}{}", matrix.reward, matrix.reward, annotation("Mutual Benefit"))
+            .bright_green()
     );
     println!(
         "  {} {}",
-        "[+] You Cooperate, Opponent Defects:".yellow(),
-        "0 / 5 (Sucker's Payoff)".bright_yellow()
+        format!("[+] You {}, Opponent {}:", cooperate, defect).yellow(),
+        format!("{} / {}{}", matrix.sucker, matrix.temptation, annotation("Sucker's Payoff"))
+            .bright_yellow()
     );
     println!(
         "  {} {}",
-        "[-] You Defect, Opponent Cooperates:".red(),
-        "5 / 0 (Temptation Payoff)".bright_red()
+        format!("[-] You {}, Opponent {}:", defect, cooperate).red(),
+        format!("{} / {}{}", matrix.temptation, matrix.sucker, annotation("Temptation Payoff"))
+            .bright_red()
     );
     println!(
         "  {} {}",
-        "[X] Both Defect:".magenta(),
-        "1 / 1 (Mutual Punishment)".bright_magenta()
+        format!("[X] Both {}:", defect).magenta(),
+        format!(
+            "{} / {}{}",
+            matrix.punishment,
+            matrix.punishment,
+            annotation("Mutual Punishment")
+        )
+        .bright_magenta()
     );
     println!("{}", "═".repeat(60).bright_black());
+    if is_dilemma {
+        matrix.print_warnings();
+    }
+    println!();
+}
+
+fn choose_game() -> GameKind {
+    println!("{}", "Choose a Game:".yellow().bold());
+    println!();
+    for (i, game) in GameKind::ALL.iter().enumerate() {
+        println!(
+            "  {} - {}",
+            format!("[{}] {}", i + 1, game.name().to_uppercase()).cyan().bold(),
+            game.description()
+        );
+    }
+    println!();
+
+    let choice = read_number("Select game", 1, GameKind::ALL.len() as u32, 1);
+    GameKind::ALL[choice as usize - 1]
+}
+
+fn choose_payoff_matrix() -> PayoffMatrix {
+    let mut options = payoff::presets();
+    if let Some(matrix) = PayoffMatrix::from_config() {
+        options.insert(0, ("Custom (payoff_matrix.json)", matrix));
+    }
+
+    println!("{}", "Choose Payoff Matrix:".yellow().bold());
+    println!();
+    for (i, (name, matrix)) in options.iter().enumerate() {
+        println!(
+            "  {} - T={} R={} P={} S={}",
+            format!("[{}] {}", i + 1, name.to_uppercase()).cyan().bold(),
+            matrix.temptation,
+            matrix.reward,
+            matrix.punishment,
+            matrix.sucker
+        );
+    }
     println!();
+
+    let choice = read_number("Select payoff matrix", 1, options.len() as u32, 1);
+    options[choice as usize - 1].1
 }
 
-fn print_difficulty_menu() -> Difficulty {
+fn print_difficulty_menu() -> Box<dyn Strategy> {
+    let mut strategies = strategy::registry();
+    let level_count = strategy::difficulty_levels().len();
+    let bots_from = level_count + strategy::classic_strategies().len();
+    let palette = [
+        colored::Color::Green,
+        colored::Color::Yellow,
+        colored::Color::Red,
+        colored::Color::Magenta,
+    ];
+
     println!("{}", "Choose Difficulty Level:".yellow().bold());
     println!();
-    println!("  {} - Computer cooperates 70% of the time", "[1] EASY".green().bold());
-    println!(
-        "  {} - Computer uses pure tit-for-tat",
-        "[2] MEDIUM".yellow().bold()
-    );
-    println!(
-        "  {} - Computer defects strategically 40% of the time",
-        "[3] HARD".red().bold()
-    );
-    println!("  {} - Computer is unpredictable and ruthless", "[4] LEGENDARY".magenta().bold());
+    for (i, strategy) in strategies.iter().enumerate() {
+        if i == level_count {
+            println!();
+            println!("{}", "Or face a classic strategy:".yellow().bold());
+            println!();
+        } else if i == bots_from {
+            println!();
+            println!("{}", "Or face one of your bots:".yellow().bold());
+            println!();
+        }
+        let label = format!("[{}] {}", i + 1, strategy.name().to_uppercase());
+        let label = if i < level_count {
+            label.color(palette[i % palette.len()]).bold()
+        } else {
+            label.bright_blue().bold()
+        };
+        println!("  {} - {}", label, strategy.description());
+    }
     println!();
 
+    let prompt = format!("Select difficulty (1-{})", strategies.len());
     loop {
-        print!("{}: ", "Select difficulty (1-4)".cyan().bold());
+        print!("{}: ", prompt.cyan().bold());
         io::stdout().flush().unwrap();
 
         let mut input = String::new();
@@ -203,13 +668,15 @@ fn print_difficulty_menu() -> Difficulty {
             .read_line(&mut input)
             .expect("Failed to read line");
 
-        match input.trim() {
-            "1" => return Difficulty::Easy,
-            "2" => return Difficulty::Medium,
-            "3" => return Difficulty::Hard,
-            "4" => return Difficulty::Legendary,
+        match input.trim().parse::<usize>() {
+            Ok(choice) if (1..=strategies.len()).contains(&choice) => {
+                return strategies.swap_remove(choice - 1);
+            }
             _ => {
-                println!("{}", "Invalid choice! Please enter 1-4.".red());
+                println!(
+                    "{}",
+                    format!("Invalid choice! Please enter 1-{}.", strategies.len()).red()
+                );
             }
         }
     }
@@ -235,11 +702,12 @@ fn print_game_state(state: &GameState) {
         state.computer_score.to_string().yellow()
     };
 
+    let (player, computer) = state.players().unwrap_or(("You", "Computer"));
     println!(
         "  {} {} │ {} {}",
-        "You:".cyan().bold(),
+        format!("{}:", player).cyan().bold(),
         player_color,
-        "Computer:".magenta().bold(),
+        format!("{}:", computer).magenta().bold(),
         computer_color
     );
     println!();
@@ -255,16 +723,28 @@ fn animate_choice(choice_text: &str, emoji: &str) {
     println!(" {}", emoji);
 }
 
-fn get_player_move() -> Move {
+/// Returns `None` when the player types `save` instead of a move, which is only
+/// offered if `can_save` is set.
+fn get_player_move(game: GameKind, can_save: bool) -> Option<Move> {
     println!();
     println!("{}", "Your Turn - Choose your strategy:".yellow().bold());
     println!();
-    animate_choice("[1] COOPERATE", "[C]");
-    println!("       Trust and work together for mutual benefit");
+    animate_choice(
+        &format!("[1] {}", game.move_label(Move::Cooperate).to_uppercase()),
+        "[C]",
+    );
+    println!("       {}", game.move_hint(Move::Cooperate));
     println!();
-    animate_choice("[2] DEFECT", "[D]");
-    println!("       Act in self-interest and betray");
+    animate_choice(
+        &format!("[2] {}", game.move_label(Move::Defect).to_uppercase()),
+        "[D]",
+    );
+    println!("       {}", game.move_hint(Move::Defect));
     println!();
+    if can_save {
+        println!("{}", "       (type 'save' to save and quit)".bright_black());
+        println!();
+    }
 
     loop {
         print!("{}: ", "Your choice (1 or 2)".cyan().bold());
@@ -276,8 +756,9 @@ fn get_player_move() -> Move {
             .expect("Failed to read line");
 
         match input.trim() {
-            "1" => return Move::Cooperate,
-            "2" => return Move::Defect,
+            "1" => return Some(Move::Cooperate),
+            "2" => return Some(Move::Defect),
+            "save" | "s" if can_save => return None,
             _ => {
                 println!("{}", "[!] Invalid input. Please enter 1 or 2.".red());
             }
@@ -285,110 +766,51 @@ fn get_player_move() -> Move {
     }
 }
 
-fn get_computer_move(history: &[(Move, Move)], difficulty: Difficulty) -> Move {
-    let mut rng = rand::thread_rng();
-
-    match difficulty {
-        Difficulty::Easy => {
-            if rng.gen_bool(0.7) {
-                Move::Cooperate
-            } else {
-                Move::Defect
-            }
-        }
-        Difficulty::Medium => {
-            if history.is_empty() {
-                Move::Cooperate
-            } else {
-                let last_player_move = history.last().unwrap().0;
-                if rng.gen_bool(0.85) {
-                    last_player_move
-                } else {
-                    Move::Defect
-                }
-            }
-        }
-        Difficulty::Hard => {
-            if history.is_empty() {
-                if rng.gen_bool(0.6) {
-                    Move::Cooperate
-                } else {
-                    Move::Defect
-                }
-            } else {
-                let player_defect_rate = history
-                    .iter()
-                    .filter(|(p, _)| *p == Move::Defect)
-                    .count() as f32
-                    / history.len() as f32;
-
-                if player_defect_rate > 0.4 {
-                    Move::Defect
-                } else if rng.gen_bool(0.6) {
-                    Move::Cooperate
-                } else {
-                    Move::Defect
-                }
-            }
-        }
-        Difficulty::Legendary => {
-            if history.is_empty() {
-                if rng.gen_bool(0.5) {
-                    Move::Cooperate
-                } else {
-                    Move::Defect
-                }
-            } else {
-                let player_defect_rate = history
-                    .iter()
-                    .filter(|(p, _)| *p == Move::Defect)
-                    .count() as f32
-                    / history.len() as f32;
-
-                let last_move = history.last().unwrap().0;
-                let mut strategy = if player_defect_rate > 0.3 {
-                    Move::Defect
-                } else if last_move == Move::Defect {
-                    Move::Defect
-                } else if rng.gen_bool(0.5) {
-                    Move::Defect
-                } else {
-                    Move::Cooperate
-                };
-
-                if rng.gen_bool(0.15) {
-                    strategy = if strategy == Move::Cooperate {
-                        Move::Defect
-                    } else {
-                        Move::Cooperate
-                    };
-                }
-
-                strategy
-            }
-        }
-    }
-}
-
 fn animate_round_result(
+    game: GameKind,
+    players: Option<(&str, &str)>,
+    intended: (Move, Move),
     player_move: Move,
     computer_move: Move,
     player_points: i32,
     computer_points: i32,
 ) {
     thread::sleep(Duration::from_millis(800));
+    print_round_result(
+        game,
+        players,
+        intended,
+        player_move,
+        computer_move,
+        player_points,
+        computer_points,
+    );
+    thread::sleep(Duration::from_millis(1500));
+}
 
-    let player_str = if player_move == Move::Cooperate {
-        "[C] COOPERATE".green()
-    } else {
-        "[D] DEFECT".red()
-    };
-
-    let computer_str = if computer_move == Move::Cooperate {
-        "[C] COOPERATE".green()
-    } else {
-        "[D] DEFECT".red()
+/// `intended` holds the moves chosen before noise; any that were flipped are
+/// pointed out under the result. `players` names both sides of a game between
+/// two people.
+fn print_round_result(
+    game: GameKind,
+    players: Option<(&str, &str)>,
+    intended: (Move, Move),
+    player_move: Move,
+    computer_move: Move,
+    player_points: i32,
+    computer_points: i32,
+) {
+    let move_str = |m: Move| {
+        let label = game.move_label(m).to_uppercase();
+        if m == Move::Cooperate {
+            format!("[C] {}", label).green()
+        } else {
+            format!("[D] {}", label).red()
+        }
     };
+    let player_str = move_str(player_move);
+    let computer_str = move_str(computer_move);
+    let (player, computer) = players.unwrap_or(("You", "Computer"));
 
     println!("\n{}", "╔════════════════════════════════════════════╗".bright_cyan());
     println!(
@@ -399,12 +821,12 @@ fn animate_round_result(
 
     println!(
         "{}",
-        format!("║  You:     {}                      ║", player_str)
+        format!("║  {:<8} {}                      ║", format!("{}:", player), player_str)
             .bright_cyan()
     );
     println!(
         "{}",
-        format!("║  Computer: {}                    ║", computer_str)
+        format!("║  {:<8} {}                    ║", format!("{}:", computer), computer_str)
             .bright_cyan()
     );
 
@@ -428,29 +850,101 @@ fn animate_round_result(
 
     println!(
         "{}",
-        format!("║  You earned: {} points                   ║", player_color)
+        format!("║  {} earned: {} points                   ║", player, player_color)
             .bright_cyan()
     );
     println!(
         "{}",
-        format!("║  Computer earned: {} points             ║", computer_color)
+        format!("║  {} earned: {} points             ║", computer, computer_color)
             .bright_cyan()
     );
 
     println!("{}", "╚════════════════════════════════════════════╝".bright_cyan());
 
-    if player_points > computer_points {
+    let label = |m: Move| game.move_label(m).to_uppercase();
+    if intended.0 != player_move {
+        println!(
+            "{}",
+            format!(
+                "~ Noise! You chose {}, but your hand slipped and you played {}.",
+                label(intended.0),
+                label(player_move)
+            )
+            .magenta()
+            .bold()
+        );
+    }
+    if intended.1 != computer_move {
+        println!(
+            "{}",
+            format!(
+                "~ Noise! The computer chose {}, but it came out as {}.",
+                label(intended.1),
+                label(computer_move)
+            )
+            .magenta()
+            .bold()
+        );
+    }
+
+    if let Some((first, second)) = players.filter(|_| player_points != computer_points) {
+        let winner = if player_points > computer_points {
+            first
+        } else {
+            second
+        };
+        println!(
+            "\n{}",
+            format!(">> {} WINS THIS ROUND! <<", winner.to_uppercase())
+                .bright_green()
+                .bold()
+        );
+    } else if player_points > computer_points {
         println!("\n{}", ">> YOU WIN THIS ROUND! <<".bright_green().bold());
     } else if player_points < computer_points {
         println!("\n{}", ">> COMPUTER WINS THIS ROUND! <<".bright_red().bold());
     } else {
         println!("\n{}", ">> BOTH EARNED EQUALLY <<".yellow().bold());
     }
+}
 
-    thread::sleep(Duration::from_millis(1500));
+/// Running totals for both sides, starting from zero before the first round.
+fn print_score_chart(state: &GameState) {
+    let mut player = vec![0.0];
+    let mut computer = vec![0.0];
+    for &(player_move, computer_move) in &state.history {
+        let (player_points, computer_points) = state.calculate_payoff(player_move, computer_move);
+        player.push(player.last().unwrap() + player_points as f64);
+        computer.push(computer.last().unwrap() + computer_points as f64);
+    }
+
+    let (player_label, computer_label) = state.players().unwrap_or(("You", &state.opponent));
+    println!("{}", "SCORE BY ROUND".yellow().bold());
+    chart::print_line_chart(
+        &[
+            chart::Series {
+                label: player_label.to_string(),
+                values: player,
+                color: colored::Color::BrightCyan,
+            },
+            chart::Series {
+                label: computer_label.to_string(),
+                values: computer,
+                color: colored::Color::BrightMagenta,
+            },
+        ],
+        10,
+        &|value| format!("{:.0}", value),
+    );
+    println!(
+        "  {}",
+        format!("round 0 to {}", state.history.len()).bright_black()
+    );
 }
 
-fn display_game_summary(state: &GameState, stats: &Statistics) {
+/// Shows how the game went, followed by the player's statistics when the game
+/// counted towards them.
+fn display_game_summary(state: &GameState, stats: Option<&Statistics>) {
     clear_screen();
 
     println!("{}", "╔═══════════════════════════════════════════════════════════╗".bright_cyan());
@@ -483,14 +977,24 @@ fn display_game_summary(state: &GameState, stats: &Statistics) {
         state.computer_score.to_string().yellow()
     };
 
+    let (your_label, computer_label) = match state.players() {
+        Some((first, second)) => (
+            format!("{}'s Final Score:", first),
+            format!("{}'s Final Score:", second),
+        ),
+        None => (
+            "Your Final Score:".to_string(),
+            "Computer Final Score:".to_string(),
+        ),
+    };
     println!(
         "  {} {}",
-        "Your Final Score:".cyan().bold(),
+        your_label.cyan().bold(),
         your_score_color
     );
     println!(
         "  {} {}",
-        "Computer Final Score:".magenta().bold(),
+        computer_label.magenta().bold(),
         computer_score_color
     );
     println!(
@@ -510,7 +1014,29 @@ fn display_game_summary(state: &GameState, stats: &Statistics) {
     );
     println!();
 
-    if state.player_score > state.computer_score {
+    if state.history.len() > 1 {
+        print_score_chart(state);
+        println!();
+    }
+
+    if let Some((first, second)) = state.players().filter(|_| final_diff != 0) {
+        let (winner, loser) = if final_diff > 0 {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        println!(
+            "{}",
+            format!("[WIN] VICTORY! {} WON! [WIN]", winner.to_uppercase())
+                .bright_green()
+                .bold()
+        );
+        println!();
+        println!(
+            "{}",
+            format!("{} outmaneuvered {} and claimed victory!", winner, loser).green()
+        );
+    } else if state.player_score > state.computer_score {
         println!("{}", "[WIN] VICTORY! YOU WON! [WIN]".bright_green().bold());
         println!();
         println!(
@@ -534,15 +1060,85 @@ fn display_game_summary(state: &GameState, stats: &Statistics) {
     }
 
     println!();
+    match state.expected_length() {
+        Some(expected) => println!(
+            "{}",
+            format!(
+                "Total Rounds Played: {} (about {:.0} expected)",
+                state.total_rounds, expected
+            )
+            .cyan()
+        ),
+        None => println!(
+            "{}",
+            format!("Total Rounds Played: {}", state.total_rounds).cyan()
+        ),
+    }
     println!(
         "{}",
-        format!("Total Rounds Played: {}", state.total_rounds).cyan()
-    );
-    println!(
-        "{}",
-        format!("Difficulty: {:?}", state.difficulty).yellow()
+        format!("Game: {}", state.game.name()).yellow()
     );
+    match state.players() {
+        Some((first, second)) => println!(
+            "{}",
+            format!("Players: {} vs {}", first, second).yellow()
+        ),
+        None => println!(
+            "{}",
+            format!("Opponent: {}", state.opponent).yellow()
+        ),
+    }
+    if state.noise > 0.0 {
+        let (player_flips, computer_flips) = state.flips();
+        println!(
+            "{}",
+            format!(
+                "Noise: {:.0}% ({} of your moves and {} of the computer's were flipped)",
+                state.noise * 100.0,
+                player_flips,
+                computer_flips
+            )
+            .magenta()
+        );
+    }
+
+    // Only the computer's choices can be replayed from the seed.
+    if state.players().is_none() {
+        let mut replay = format!(
+            "game_theory play --game {} --difficulty {}",
+            state.game.slug(),
+            strategy::slug(&state.opponent)
+        );
+        match state.continuation {
+            Some(continuation) => {
+                replay.push_str(&format!(" --continuation {:.0}", continuation * 100.0))
+            }
+            None => replay.push_str(&format!(" --rounds {}", state.total_rounds)),
+        }
+        if state.noise > 0.0 {
+            replay.push_str(&format!(" --noise {:.0}", state.noise * 100.0));
+        }
+        replay.push_str(&format!(" --seed {}", state.seed));
+        println!("{}", format!("Seed: {}", state.seed).bright_black());
+        println!("{}", format!("Replay with: {}", replay).bright_black());
+        // `play` takes the Prisoner's Dilemma payoffs from the config file.
+        if state.game == GameKind::PrisonersDilemma && state.payoffs != PayoffMatrix::CLASSIC {
+            let p = state.payoffs;
+            println!(
+                "{}",
+                format!(
+                    "  (with {{ \"temptation\": {}, \"reward\": {}, \"punishment\": {}, \"sucker\": {} }} in payoff_matrix.json)",
+                    p.temptation, p.reward, p.punishment, p.sucker
+                )
+                .bright_black()
+            );
+        }
+    }
     println!();
+
+    let Some(stats) = stats else {
+        return;
+    };
     println!("{}", "═".repeat(60).bright_black());
     println!("{}", "YOUR STATISTICS".yellow().bold());
     println!("{}", "═".repeat(60).bright_black());
@@ -567,20 +1163,304 @@ fn display_game_summary(state: &GameState, stats: &Statistics) {
     println!();
 }
 
-fn main_menu() -> u32 {
-    print_title();
-    println!("{}", "═".repeat(60).bright_black());
-    println!("{}", "MAIN MENU".yellow().bold());
-    println!("{}", "═".repeat(60).bright_black());
+/// Asks whether the game has a fixed number of rounds or an unknown length.
+fn read_length() -> Length {
+    println!("{}", "Game length:".cyan().bold());
+    println!("  [1] A fixed number of rounds");
+    println!("  [2] Unknown - after each round the game goes on with some probability");
+    if read_number("Your choice", 1, 2, 1) == 1 {
+        return Length::Rounds(read_rounds());
+    }
+    println!(
+        "{}",
+        "With no known last round, there is no safe moment to betray.".bright_black()
+    );
+    let percent = read_number("Chance of another round, in percent", 1, 99, 90);
+    Length::Continuation(percent as f64 / 100.0)
+}
+
+fn read_rounds() -> u32 {
+    loop {
+        print!("{}: ", "How many rounds? (1-50)".cyan().bold());
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        if let Ok(rounds) = input.trim().parse::<u32>() {
+            if (1..=50).contains(&rounds) {
+                return rounds;
+            } else {
+                println!("{}", "Please enter a number between 1 and 50.".red());
+            }
+        } else {
+            println!("{}", "Invalid input. Please enter a number.".red());
+        }
+    }
+}
+
+/// Asks for the noise level as a percentage and returns it as a probability.
+fn read_noise() -> f64 {
+    println!(
+        "{}",
+        "Noise makes each move come out as the opposite one now and then, for you and the computer."
+            .bright_black()
+    );
+    read_number("Noise in percent", 0, 50, 0) as f64 / 100.0
+}
+
+/// The payoffs for `game`, asking for the game first if it isn't given. Only
+/// the Prisoner's Dilemma lets the player pick a matrix.
+fn choose_game_and_payoffs(game: Option<GameKind>) -> (GameKind, PayoffMatrix) {
+    match game {
+        Some(GameKind::PrisonersDilemma) => {
+            (GameKind::PrisonersDilemma, PayoffMatrix::default_matrix())
+        }
+        Some(game) => (game, game.payoffs()),
+        None => {
+            let game = choose_game();
+            if game == GameKind::PrisonersDilemma {
+                println!();
+                (game, choose_payoff_matrix())
+            } else {
+                (game, game.payoffs())
+            }
+        }
+    }
+}
+
+/// Runs games against the computer until the player stops. Anything left as
+/// `None` is asked for interactively; a missing seed is picked at random.
+fn play(
+    game: Option<GameKind>,
+    opponent: Option<Box<dyn Strategy>>,
+    length: Option<Length>,
+    noise: Option<f64>,
+    mut seed: Option<u64>,
+) {
+    print_title();
+    if let Some(saved) = save::load() {
+        print!(
+            "{}: ",
+            format!(
+                "A new game replaces your saved game vs {} (round {}). Continue? (y/n)",
+                saved.opponent,
+                saved.round + 1
+            )
+            .yellow()
+            .bold()
+        );
+        io::stdout().flush().unwrap();
+
+        let mut confirm = String::new();
+        io::stdin()
+            .read_line(&mut confirm)
+            .expect("Failed to read line");
+        if !confirm.trim().eq_ignore_ascii_case("y") {
+            return;
+        }
+        print_title();
+    }
+    let (game, payoffs) = choose_game_and_payoffs(game);
+    print_title();
+    print_payoff_matrix(&payoffs, game);
+
+    let mut opponent = match opponent {
+        Some(opponent) => opponent,
+        None => {
+            let opponent = print_difficulty_menu();
+            println!("{}", "Excellent choice! Let's play!".bright_green().bold());
+            println!();
+            opponent
+        }
+    };
+    let noise = noise.unwrap_or_else(read_noise);
+
+    loop {
+        // A requested seed only applies to the first game; replays get fresh ones.
+        let seed = seed.take().unwrap_or_else(rand::random);
+        let mut state = match length.unwrap_or_else(read_length) {
+            Length::Rounds(rounds) => {
+                GameState::new(rounds, opponent.name(), game, payoffs, noise, seed)
+            }
+            Length::Continuation(continuation) => {
+                GameState::open_ended(continuation, opponent.name(), game, payoffs, noise, seed)
+            }
+        };
+        opponent.reset();
+
+        if !play_rounds(&mut state, opponent.as_mut()) {
+            return;
+        }
+        finish_game(&state);
+
+        print!("{}: ", "Play again? (y/n)".cyan().bold());
+        io::stdout().flush().unwrap();
+
+        let mut play_again = String::new();
+        io::stdin()
+            .read_line(&mut play_again)
+            .expect("Failed to read line");
+
+        if play_again.trim().to_lowercase() != "y" {
+            break;
+        }
+    }
+}
+
+/// Plays the remaining rounds of `state`, autosaving after each one so an
+/// interrupted game can be resumed. Returns `false` if the player saved and quit.
+fn play_rounds(state: &mut GameState, opponent: &mut dyn Strategy) -> bool {
+    let mut autosave_failed = false;
+    while state.round < state.total_rounds {
+        state.round += 1;
+        clear_screen();
+        print_title();
+        print_game_state(state);
+
+        let Some(player_move) = get_player_move(state.game, true) else {
+            state.round -= 1;
+            if let Err(error) = save::save(state) {
+                warn(&format!(
+                    "The game could not be saved: {}. Keep playing or try again.",
+                    error
+                ));
+                continue;
+            }
+            println!();
+            println!(
+                "{}",
+                "Game saved! Choose RESUME from the main menu to continue."
+                    .bright_green()
+                    .bold()
+            );
+            thread::sleep(Duration::from_millis(1500));
+            return false;
+        };
+        let intended = (player_move, opponent.next_move(&state.history, &mut state.rng));
+        let player_move = state.tremble(intended.0);
+        let computer_move = state.tremble(intended.1);
+
+        let (player_points, computer_points) = state.calculate_payoff(player_move, computer_move);
+
+        animate_round_result(
+            state.game,
+            None,
+            intended,
+            player_move,
+            computer_move,
+            player_points,
+            computer_points,
+        );
+
+        state.player_score += player_points;
+        state.computer_score += computer_points;
+        state.history.push((player_move, computer_move));
+        state.intended.push(intended);
+        state.draw_continuation();
+        if let Err(error) = save::save(state) {
+            // Once is enough; the game goes on either way.
+            if !autosave_failed {
+                warn(&format!(
+                    "The game could not be autosaved and can't be resumed if interrupted: {}.",
+                    error
+                ));
+                autosave_failed = true;
+            }
+        }
+    }
+
+    save::delete();
+    true
+}
+
+/// Records a completed game in the statistics and history, then shows the summary.
+fn finish_game(state: &GameState) {
+    let mut stats = Statistics::load();
+    stats.record(state);
+    if let Err(error) = stats.save() {
+        warn(&format!("Your statistics were not saved: {}.", error));
+    }
+    if let Err(error) = history::append(GameRecord::from_state(state)) {
+        warn(&format!("This game was not added to your history: {}.", error));
+    }
+
+    display_game_summary(state, Some(&stats));
+
+    println!();
+    print!("{}: ", "Press Enter to continue".cyan());
+    io::stdout().flush().unwrap();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+fn resume_game() {
+    let Some(mut state) = save::load() else {
+        println!("{}", "[!] The saved game could not be read.".red());
+        thread::sleep(Duration::from_millis(1500));
+        return;
+    };
+    let Some(mut opponent) = save::restore_opponent(&state) else {
+        println!(
+            "{}",
+            format!("[!] Unknown opponent '{}' in the saved game.", state.opponent).red()
+        );
+        thread::sleep(Duration::from_millis(1500));
+        return;
+    };
+
+    if play_rounds(&mut state, opponent.as_mut()) {
+        finish_game(&state);
+    }
+}
+
+fn main_menu() -> u32 {
+    print_title();
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "MAIN MENU".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
     println!();
     println!("  [1] [>] PLAY - Start a new game", );
     println!("  [2] [@] STATS - View your statistics");
     println!("  [3] [?] RULES - How to play");
-    println!("  [4] [X] QUIT - Exit game");
+    println!("  [4] [#] TOURNAMENT - Pit every strategy against each other");
+    println!("  [5] [~] HISTORY - Browse your past games");
+    println!("  [6] [&] PROFILES - Switch or manage players");
+    println!("  [7] [^] LEADERBOARD - Compare every profile");
+    println!("  [8] [%] EVOLUTION - Watch strategies compete for survival");
+    println!("  [9] [+] SPATIAL - Watch strategies spread across a grid");
+    println!("  [10] [=] HOTSEAT - Two players on one keyboard");
+    println!("  [11] [*] NETWORK - Play someone on another computer");
+    println!("  [12] [X] QUIT - Exit game");
+    let saved = save::load();
+    if let Some(state) = &saved {
+        println!(
+            "  {}",
+            match state.continuation {
+                Some(_) => format!(
+                    "[R] [<] RESUME - Continue your saved game vs {} (round {})",
+                    state.opponent,
+                    state.round + 1
+                ),
+                None => format!(
+                    "[R] [<] RESUME - Continue your saved game vs {} (round {}/{})",
+                    state.opponent,
+                    state.round + 1,
+                    state.total_rounds
+                ),
+            }
+            .bright_green()
+        );
+    }
     println!();
+    let options = if saved.is_some() { "1-12 or R" } else { "1-12" };
 
     loop {
-        print!("{}: ", "Select an option (1-4)".cyan().bold());
+        print!(
+            "{}: ",
+            format!("Select an option ({})", options).cyan().bold()
+        );
         io::stdout().flush().unwrap();
 
         let mut input = String::new();
@@ -588,13 +1468,16 @@ fn main_menu() -> u32 {
             .read_line(&mut input)
             .expect("Failed to read line");
 
-        match input.trim() {
-            "1" => return 1,
-            "2" => return 2,
-            "3" => return 3,
-            "4" => return 4,
+        if saved.is_some() && input.trim().eq_ignore_ascii_case("r") {
+            return 0;
+        }
+        match input.trim().parse::<u32>() {
+            Ok(choice) if (1..=12).contains(&choice) => return choice,
             _ => {
-                println!("{}", "[!] Invalid choice! Please enter 1-4.".red());
+                println!(
+                    "{}",
+                    format!("[!] Invalid choice! Please enter {}.", options).red()
+                );
             }
         }
     }
@@ -602,20 +1485,103 @@ fn main_menu() -> u32 {
 
 fn display_rules() {
     print_title();
-    print_payoff_matrix();
+    let game = choose_game();
+    print_title();
+    print_rules(game);
+    println!();
+    println!("{}", "═".repeat(60).bright_black());
+    print!("{}: ", "Press Enter to return to menu".cyan());
+    io::stdout().flush().unwrap();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+fn print_rules(game: GameKind) {
+    let payoffs = if game == GameKind::PrisonersDilemma {
+        PayoffMatrix::default_matrix()
+    } else {
+        game.payoffs()
+    };
+
+    print_payoff_matrix(&payoffs, game);
     println!();
     println!("{}", "GAME RULES & STRATEGY TIPS".yellow().bold());
     println!("{}", "═".repeat(60).bright_black());
     println!();
-    println!("{}" ,"1. Each round, you and the computer choose to COOPERATE or DEFECT".cyan());
+    println!(
+        "{}",
+        format!(
+            "1. Each round, you and the computer choose to {} or {}",
+            game.move_label(Move::Cooperate).to_uppercase(),
+            game.move_label(Move::Defect).to_uppercase()
+        )
+        .cyan()
+    );
     println!("{}" ,"2. Your combined choices determine points earned this round".cyan());
     println!("{}" ,"3. The player with the highest score after all rounds WINS!".cyan());
     println!();
     println!("{}", "STRATEGIC TIPS:".bright_yellow().bold());
-    println!("{}" ,"  + Cooperate for steady gains but risk being exploited".green());
-    println!("{}" ,"  - Defect for short-term advantage but risk mutual punishment".red());
-    println!("{}" ,"  * Pay attention to opponent patterns and adapt".magenta());
-    println!("{}" ,"  ^ Mix strategies to keep opponent guessing".yellow());
+    let palette = [
+        colored::Color::Green,
+        colored::Color::Red,
+        colored::Color::Magenta,
+        colored::Color::Yellow,
+    ];
+    for (i, tip) in game.tips().iter().enumerate() {
+        println!("{}", format!("  {}", tip).color(palette[i % palette.len()]));
+    }
+}
+
+fn read_number(prompt: &str, min: u32, max: u32, default: u32) -> u32 {
+    loop {
+        print!(
+            "{}: ",
+            format!("{} ({}-{}, Enter for {})", prompt, min, max, default)
+                .cyan()
+                .bold()
+        );
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        if input.trim().is_empty() {
+            return default;
+        }
+        match input.trim().parse::<u32>() {
+            Ok(n) if (min..=max).contains(&n) => return n,
+            _ => {
+                println!(
+                    "{}",
+                    format!("Please enter a number between {} and {}.", min, max).red()
+                );
+            }
+        }
+    }
+}
+
+fn display_tournament() {
+    print_title();
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "ROUND-ROBIN TOURNAMENT".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    println!();
+
+    let rounds = read_number("Rounds per match", 1, 1000, tournament::DEFAULT_ROUNDS);
+    let repetitions = read_number("Repetitions", 1, 100, tournament::DEFAULT_REPETITIONS);
+    let noise = read_noise();
+    println!();
+    let payoffs = choose_payoff_matrix();
+    payoffs.print_warnings();
+
+    println!();
+    println!("{}", "Running tournament...".bright_black());
+    let result = tournament::run(rounds, repetitions, payoffs, noise, rand::random());
+
+    print_title();
+    tournament::print_results(&result);
+
     println!();
     println!("{}", "═".repeat(60).bright_black());
     print!("{}: ", "Press Enter to return to menu".cyan());
@@ -623,8 +1589,427 @@ fn display_rules() {
     let _ = io::stdin().read_line(&mut String::new());
 }
 
+/// Asks which strategies make up the population, at most `limit` of them. An
+/// empty answer is returned as is; `empty_means` tells the player what it does.
+fn read_strategy_selection(empty_means: &str, limit: usize) -> Vec<String> {
+    let names: Vec<String> = strategy::registry()
+        .iter()
+        .map(|s| s.name().to_string())
+        .collect();
+    for (i, name) in names.iter().enumerate() {
+        println!("  [{:>2}] {}", i + 1, name);
+    }
+
+    loop {
+        print!(
+            "{}: ",
+            format!(
+                "Strategies (numbers separated by commas, Enter for {})",
+                empty_means
+            )
+            .cyan()
+            .bold()
+        );
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        if input.trim().is_empty() {
+            return Vec::new();
+        }
+
+        let picked: Option<Vec<usize>> = input
+            .split(',')
+            .map(|n| {
+                n.trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| (1..=names.len()).contains(n))
+            })
+            .collect();
+        match picked {
+            Some(mut picked) => {
+                picked.sort_unstable();
+                picked.dedup();
+                if picked.len() > limit {
+                    println!(
+                        "{}",
+                        format!("[!] Pick at most {} strategies.", limit).red()
+                    );
+                } else if picked.len() >= 2 {
+                    return picked.iter().map(|&n| names[n - 1].clone()).collect();
+                } else {
+                    println!("{}", "[!] Pick at least two different strategies.".red());
+                }
+            }
+            None => println!(
+                "{}",
+                format!("[!] Please enter numbers between 1 and {}.", names.len()).red()
+            ),
+        }
+    }
+}
+
+fn display_evolution() {
+    print_title();
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "EVOLUTION".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    println!();
+    println!(
+        "{}",
+        "Strategies play each other, and the successful ones multiply.".bright_black()
+    );
+    println!();
+
+    let strategies = read_strategy_selection("all", usize::MAX);
+    println!();
+    for (i, dynamics) in evolution::Dynamics::ALL.iter().enumerate() {
+        println!("  [{}] {}", i + 1, dynamics.name());
+    }
+    let dynamics = evolution::Dynamics::ALL[read_number("Dynamics", 1, 2, 1) as usize - 1];
+    let population = match dynamics {
+        evolution::Dynamics::Replicator => evolution::DEFAULT_POPULATION,
+        evolution::Dynamics::Moran => {
+            read_number("Population size", 2, 1000, evolution::DEFAULT_POPULATION)
+        }
+    };
+    let generations = read_number("Generations", 1, 1000, evolution::DEFAULT_GENERATIONS);
+    let mutation = read_number("Mutation in percent", 0, 50, 0) as f64 / 100.0;
+    let noise = read_noise();
+    println!();
+    let payoffs = choose_payoff_matrix();
+    payoffs.print_warnings();
+
+    println!();
+    println!("{}", "Evolving...".bright_black());
+    let result = evolution::run(&evolution::Settings {
+        dynamics,
+        strategies,
+        generations,
+        population,
+        mutation,
+        rounds: evolution::DEFAULT_ROUNDS,
+        payoffs,
+        noise,
+        seed: rand::random(),
+    });
+
+    print_title();
+    evolution::print_results(&result);
+
+    println!();
+    println!("{}", "═".repeat(60).bright_black());
+    print!("{}: ", "Press Enter to return to menu".cyan());
+    io::stdout().flush().unwrap();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+fn display_spatial() {
+    print_title();
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "SPATIAL PRISONER'S DILEMMA".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    println!();
+    println!(
+        "{}",
+        "Every cell plays its neighbors, then copies the best scorer around it.".bright_black()
+    );
+    println!();
+
+    let mut strategies =
+        read_strategy_selection("cooperators vs defectors", spatial::MAX_STRATEGIES);
+    if strategies.is_empty() {
+        strategies = spatial::DEFAULT_STRATEGIES
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+    }
+    let equal = 100 / strategies.len() as u32;
+    let mix: Vec<f64> = strategies
+        .iter()
+        .map(|name| {
+            read_number(
+                &format!("Starting share of {} in percent", name),
+                0,
+                100,
+                equal,
+            ) as f64
+        })
+        .collect();
+    println!();
+    for (i, neighborhood) in spatial::Neighborhood::ALL.iter().enumerate() {
+        println!(
+            "  [{}] {} ({} neighbors)",
+            i + 1,
+            neighborhood.name(),
+            neighborhood.neighbors()
+        );
+    }
+    let neighborhood =
+        spatial::Neighborhood::ALL[read_number("Neighborhood", 1, 2, 2) as usize - 1];
+    let size = read_number("Grid size", 5, spatial::MAX_SIZE, spatial::DEFAULT_SIZE);
+    let generations = read_number("Generations", 1, 1000, spatial::DEFAULT_GENERATIONS);
+    println!();
+    let payoffs = choose_payoff_matrix();
+    payoffs.print_warnings();
+
+    spatial::run(&spatial::Settings {
+        size,
+        neighborhood,
+        strategies,
+        mix,
+        generations,
+        rounds: spatial::DEFAULT_ROUNDS,
+        payoffs,
+        seed: rand::random(),
+    });
+
+    println!();
+    println!("{}", "═".repeat(60).bright_black());
+    print!("{}: ", "Press Enter to return to menu".cyan());
+    io::stdout().flush().unwrap();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+fn display_network() {
+    print_title();
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "NETWORK GAME".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    println!();
+    println!("  [1] Host a game and wait for someone to join");
+    println!("  [2] Join a game someone else is hosting");
+    println!();
+    if read_number("Your choice", 1, 2, 1) == 1 {
+        let port = read_number("Port", 1024, u16::MAX as u32, network::DEFAULT_PORT as u32);
+        network::host(port as u16, None, None);
+    } else {
+        network::join(None);
+    }
+}
+
+/// The player's recorded games, or none (with a warning) if they can't be read.
+fn load_history() -> Vec<GameRecord> {
+    history::load().unwrap_or_else(|error| {
+        warn(&format!("Could not load your game history: {}.", error));
+        Vec::new()
+    })
+}
+
+fn display_history() {
+    let records = load_history();
+
+    loop {
+        print_title();
+        println!("{}", "═".repeat(60).bright_black());
+        println!("{}", "GAME HISTORY".yellow().bold());
+        println!("{}", "═".repeat(60).bright_black());
+        println!();
+
+        if records.is_empty() {
+            println!("{}", "No games recorded yet. Finished games will show up here!".yellow());
+            println!();
+            println!("{}", "═".repeat(60).bright_black());
+            print!("{}: ", "Press Enter to return to menu".cyan());
+            io::stdout().flush().unwrap();
+            let _ = io::stdin().read_line(&mut String::new());
+            return;
+        }
+
+        println!(
+            "  {}",
+            format!(
+                "{:>4}  {:<16}  {:<20}  {:<22}  {:>7}  {:>4}",
+                "#", "Date (UTC)", "Game", "Opponent", "Score", ""
+            )
+            .cyan()
+            .bold()
+        );
+        for (i, record) in records.iter().enumerate().rev() {
+            let result = match record.result_label() {
+                "WIN" => "WIN".bright_green().bold(),
+                "LOSS" => "LOSS".bright_red().bold(),
+                other => other.yellow().bold(),
+            };
+            println!(
+                "  {:>4}  {:<16}  {:<20}  {:<22}  {:>7}  {}",
+                i + 1,
+                record.date(),
+                record.game.name(),
+                record.opponent,
+                format!("{}-{}", record.player_score, record.computer_score),
+                result
+            );
+        }
+        println!();
+
+        print!(
+            "{}: ",
+            "Enter a game number to inspect it (Enter to return)".cyan().bold()
+        );
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        if input.trim().is_empty() {
+            return;
+        }
+        match input.trim().parse::<usize>() {
+            Ok(n) if (1..=records.len()).contains(&n) => {
+                let record = &records[n - 1];
+                print_title();
+                println!(
+                    "{}",
+                    format!("Game #{} - played {} UTC", n, record.date()).bright_black()
+                );
+                tournament::print_match(&record.to_state(), "You");
+                println!();
+                println!("{}", "═".repeat(60).bright_black());
+                print!(
+                    "{}: ",
+                    "Type R to replay this game, or press Enter to go back".cyan()
+                );
+                io::stdout().flush().unwrap();
+
+                let mut choice = String::new();
+                io::stdin()
+                    .read_line(&mut choice)
+                    .expect("Failed to read line");
+                if choice.trim().eq_ignore_ascii_case("r") {
+                    replay::run(record, n);
+                }
+            }
+            _ => {
+                println!("{}", "[!] No game with that number.".red());
+                thread::sleep(Duration::from_millis(1000));
+            }
+        }
+    }
+}
+
+fn read_profile_name(prompt: &str) -> String {
+    print!(
+        "{}: ",
+        format!("{} (max {} characters)", prompt, profile::MAX_NAME_LENGTH)
+            .cyan()
+            .bold()
+    );
+    io::stdout().flush().unwrap();
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read line");
+    input.trim().to_string()
+}
+
+fn report_profile_result(result: Result<(), String>) {
+    if let Err(message) = result {
+        println!("{}", format!("[!] {}", message).red());
+        thread::sleep(Duration::from_millis(1500));
+    }
+}
+
+/// Lets the player pick who is playing, and create, rename or delete profiles.
+fn manage_profiles() {
+    loop {
+        let names = profile::names();
+        let active = profile::active();
+
+        print_title();
+        println!("{}", "═".repeat(60).bright_black());
+        println!("{}", "PLAYER PROFILES".yellow().bold());
+        println!("{}", "═".repeat(60).bright_black());
+        println!();
+        for (i, name) in names.iter().enumerate() {
+            let games = Statistics::load_for(name).games_played;
+            let line = format!("[{}] {:<20} {} games", i + 1, name, games);
+            if *name == active {
+                println!("  {} {}", line.bright_green().bold(), "(active)".bright_green());
+            } else {
+                println!("  {}", line);
+            }
+        }
+        println!();
+        println!("  [N] New profile   [R] Rename   [D] Delete");
+        println!();
+        print!(
+            "{}: ",
+            format!("Pick a profile number or action (Enter to continue as {})", active)
+                .cyan()
+                .bold()
+        );
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        match input.trim().to_lowercase().as_str() {
+            "" => return,
+            "n" => {
+                let name = read_profile_name("New profile name");
+                report_profile_result(profile::create(&name));
+            }
+            "r" => {
+                let choice = read_number("Profile to rename", 1, names.len() as u32, 1);
+                let name = read_profile_name("New name");
+                report_profile_result(profile::rename(&names[choice as usize - 1], &name));
+            }
+            "d" => {
+                let choice = read_number("Profile to delete", 1, names.len() as u32, 1);
+                let name = &names[choice as usize - 1];
+                print!(
+                    "{}: ",
+                    format!(
+                        "Delete '{}' and all of their statistics and history? (y/n)",
+                        name
+                    )
+                    .red()
+                    .bold()
+                );
+                io::stdout().flush().unwrap();
+
+                let mut confirm = String::new();
+                io::stdin()
+                    .read_line(&mut confirm)
+                    .expect("Failed to read line");
+                if confirm.trim().eq_ignore_ascii_case("y") {
+                    report_profile_result(profile::delete(name));
+                }
+            }
+            other => match other.parse::<usize>() {
+                Ok(n) if (1..=names.len()).contains(&n) => {
+                    report_profile_result(profile::switch(&names[n - 1]));
+                    return;
+                }
+                _ => {
+                    println!("{}", "[!] Invalid choice!".red());
+                    thread::sleep(Duration::from_millis(1000));
+                }
+            },
+        }
+    }
+}
+
 fn display_stats(stats: &Statistics) {
     print_title();
+    print_stats(stats);
+    println!();
+    println!("{}", "═".repeat(60).bright_black());
+    print!("{}: ", "Press Enter to return to menu".cyan());
+    io::stdout().flush().unwrap();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+fn print_stats(stats: &Statistics) {
     println!("{}", "═".repeat(60).bright_black());
     println!("{}", "YOUR GAME STATISTICS".yellow().bold());
     println!("{}", "═".repeat(60).bright_black());
@@ -666,120 +2051,225 @@ fn display_stats(stats: &Statistics) {
         println!(
             "  {} {}",
             "Best Score Differential:".green().bold(),
-            format!("+{}", stats.best_score_differential)
+            format!("{:+}", stats.best_score_differential)
                 .bright_green()
         );
         println!(
             "  {} {}",
             "Worst Score Differential:".red().bold(),
-            format!("{}", stats.worst_score_differential).bright_red()
+            format!("{:+}", stats.worst_score_differential).bright_red()
         );
+        println!();
+        print_breakdown(stats);
+        println!();
+        analytics::print(&load_history());
     }
 
-    println!();
-    println!("{}", "═".repeat(60).bright_black());
-    print!("{}: ", "Press Enter to return to menu".cyan());
-    io::stdout().flush().unwrap();
-    let _ = io::stdin().read_line(&mut String::new());
+}
+
+fn print_breakdown(stats: &Statistics) {
+    println!("{}", "BY OPPONENT".yellow().bold());
+    println!(
+        "  {}",
+        format!(
+            "{:<22} {:>3} {:>3} {:>3} {:>6} {:>6} {:>5} {:>5}",
+            "Opponent", "W", "L", "T", "Win %", "Points", "Best", "Worst"
+        )
+        .cyan()
+        .bold()
+    );
+
+    for opponent in stats.breakdown() {
+        println!(
+            "  {:<22} {} {} {} {:>5.1}% {:>6} {:>5} {:>5}",
+            opponent.opponent,
+            format!("{:>3}", opponent.games_won).green(),
+            format!("{:>3}", opponent.games_lost).red(),
+            format!("{:>3}", opponent.games_tied).yellow(),
+            opponent.win_rate(),
+            opponent.total_points,
+            format!("{:+}", opponent.best_score_differential),
+            format!("{:+}", opponent.worst_score_differential)
+        );
+    }
+
+    let tracked: u32 = stats.by_opponent.iter().map(|o| o.games_played).sum();
+    if tracked < stats.games_played {
+        println!(
+            "  {}",
+            format!(
+                "{} earlier game(s) were played before results were kept per opponent.",
+                stats.games_played - tracked
+            )
+            .bright_black()
+        );
+    }
 }
 
 fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let parsed = cli::take_globals(&mut args)
+        .and_then(|globals| cli::parse(&args).map(|command| (globals, command)));
+    let (globals, command) = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{} {}", "error:".red().bold(), message);
+            eprintln!("Run with --help for usage.");
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(dir) = globals.data_dir {
+        profile::set_data_dir(dir);
+    }
+    if let Err(message) = profile::ensure_active() {
+        eprintln!("{} {}", "error:".red().bold(), message);
+        std::process::exit(1);
+    }
+    if let Some(name) = globals.profile {
+        if let Err(message) = profile::switch(&name) {
+            eprintln!("{} {}", "error:".red().bold(), message);
+            std::process::exit(2);
+        }
+    }
+
+    match command {
+        Command::Menu => manage_profiles(),
+        Command::Help => {
+            cli::print_help();
+            return;
+        }
+        Command::Play {
+            game,
+            opponent,
+            length,
+            noise,
+            seed,
+        } => {
+            play(Some(game), opponent, length, Some(noise), seed);
+            return;
+        }
+        Command::Stats => {
+            print_stats(&Statistics::load());
+            return;
+        }
+        Command::Rules { game } => {
+            print_rules(game);
+            return;
+        }
+        Command::Tournament {
+            rounds,
+            repetitions,
+            noise,
+            seed,
+        } => {
+            let payoffs = PayoffMatrix::default_matrix();
+            payoffs.print_warnings();
+            let seed = seed.unwrap_or_else(rand::random);
+            tournament::print_results(&tournament::run(rounds, repetitions, payoffs, noise, seed));
+            return;
+        }
+        Command::Simulate {
+            mut player,
+            mut opponent,
+            rounds,
+            noise,
+            seed,
+        } => {
+            let payoffs = PayoffMatrix::default_matrix();
+            payoffs.print_warnings();
+            let seed = seed.unwrap_or_else(rand::random);
+            let state = tournament::play_match(
+                player.as_mut(),
+                opponent.as_mut(),
+                rounds,
+                payoffs,
+                noise,
+                seed,
+            );
+            tournament::print_match(&state, player.name());
+            return;
+        }
+        Command::Evolve {
+            dynamics,
+            strategies,
+            generations,
+            population,
+            mutation,
+            rounds,
+            noise,
+            seed,
+        } => {
+            let payoffs = PayoffMatrix::default_matrix();
+            payoffs.print_warnings();
+            let result = evolution::run(&evolution::Settings {
+                dynamics,
+                strategies,
+                generations,
+                population,
+                mutation,
+                rounds,
+                payoffs,
+                noise,
+                seed: seed.unwrap_or_else(rand::random),
+            });
+            evolution::print_results(&result);
+            return;
+        }
+        Command::Spatial {
+            size,
+            neighborhood,
+            strategies,
+            mix,
+            generations,
+            rounds,
+            seed,
+        } => {
+            let payoffs = PayoffMatrix::default_matrix();
+            payoffs.print_warnings();
+            spatial::run(&spatial::Settings {
+                size,
+                neighborhood,
+                strategies,
+                mix,
+                generations,
+                rounds,
+                payoffs,
+                seed: seed.unwrap_or_else(rand::random),
+            });
+            return;
+        }
+        Command::Hotseat { game, length } => {
+            hotseat::run(Some(game), length);
+            return;
+        }
+        Command::Host { port, game, length } => {
+            network::host(port, Some(game), length);
+            return;
+        }
+        Command::Join { address } => {
+            network::join(Some(address));
+            return;
+        }
+        Command::ResetStats => {
+            if let Err(error) = Statistics::new().save() {
+                eprintln!("{} {}", "error:".red().bold(), error);
+                std::process::exit(1);
+            }
+            println!("{}", "Statistics have been reset.".bright_green().bold());
+            return;
+        }
+    }
+
     loop {
         let choice = main_menu();
 
         match choice {
+            0 => {
+                resume_game();
+            }
             1 => {
-                // Play game
-                let mut stats = Statistics::load();
-                print_title();
-                print_payoff_matrix();
-
-                let difficulty = print_difficulty_menu();
-
-                println!("{}","Excellent choice! Let's play!".bright_green().bold());
-                println!();
-
-                loop {
-                    print!("{}: ", "How many rounds? (1-50)".cyan().bold());
-                    io::stdout().flush().unwrap();
-
-                    let mut input = String::new();
-                    io::stdin()
-                        .read_line(&mut input)
-                        .expect("Failed to read line");
-
-                    if let Ok(rounds) = input.trim().parse::<u32>() {
-                        if rounds >= 1 && rounds <= 50 {
-                            let mut state = GameState::new(rounds, difficulty);
-
-                            for _ in 0..rounds {
-                                state.round += 1;
-                                clear_screen();
-                                print_title();
-                                print_game_state(&state);
-
-                                let player_move = get_player_move();
-                                let computer_move = get_computer_move(&state.history, difficulty);
-
-                                let (player_points, computer_points) =
-                                    state.calculate_payoff(player_move, computer_move);
-
-                                animate_round_result(
-                                    player_move,
-                                    computer_move,
-                                    player_points,
-                                    computer_points,
-                                );
-
-                                state.player_score += player_points;
-                                state.computer_score += computer_points;
-                                state.history.push((player_move, computer_move));
-                            }
-
-                            let score_diff = state.player_score - state.computer_score;
-                            stats.games_played += 1;
-                            stats.total_points += state.player_score;
-
-                            if state.player_score > state.computer_score {
-                                stats.games_won += 1;
-                            } else if state.player_score < state.computer_score {
-                                stats.games_lost += 1;
-                            } else {
-                                stats.games_tied += 1;
-                            }
-
-                            stats.best_score_differential =
-                                stats.best_score_differential.max(score_diff);
-                            stats.worst_score_differential =
-                                stats.worst_score_differential.min(score_diff);
-
-                            stats.save();
-
-                            display_game_summary(&state, &stats);
-
-                            println!();
-                            print!("{}: ", "Press Enter to continue".cyan());
-                            io::stdout().flush().unwrap();
-                            let _ = io::stdin().read_line(&mut String::new());
-
-                            print!("{}: ", "Play again? (y/n)".cyan().bold());
-                            io::stdout().flush().unwrap();
-
-                            let mut play_again = String::new();
-                            io::stdin()
-                                .read_line(&mut play_again)
-                                .expect("Failed to read line");
-
-                            if play_again.trim().to_lowercase() != "y" {
-                                break;
-                            }
-                        } else {
-                            println!("{}", "Please enter a number between 1 and 50.".red());
-                        }
-                    } else {
-                        println!("{}", "Invalid input. Please enter a number.".red());
-                    }
-                }
+                play(None, None, None, None, None);
             }
             2 => {
                 let stats = Statistics::load();
@@ -789,6 +2279,30 @@ fn main() {
                 display_rules();
             }
             4 => {
+                display_tournament();
+            }
+            5 => {
+                display_history();
+            }
+            6 => {
+                manage_profiles();
+            }
+            7 => {
+                leaderboard::display();
+            }
+            8 => {
+                display_evolution();
+            }
+            9 => {
+                display_spatial();
+            }
+            10 => {
+                hotseat::run(None, None);
+            }
+            11 => {
+                display_network();
+            }
+            12 => {
                 println!();
                 println!(
                     "{}",
@@ -800,3 +2314,111 @@ fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    const V1_STATS: &str = r#"{
+        "games_played": 4,
+        "games_won": 2,
+        "games_lost": 1,
+        "games_tied": 1,
+        "total_points": 30,
+        "best_score_differential": 5,
+        "worst_score_differential": -2
+    }"#;
+
+    /// An empty folder of its own for each test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("game_theory_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn migrate_upgrades_a_version_1_file() {
+        let mut value: serde_json::Value = serde_json::from_str(V1_STATS).unwrap();
+        Statistics::migrate(&mut value).unwrap();
+
+        let stats: Statistics = serde_json::from_value(value).unwrap();
+        assert_eq!(stats.version, STATS_VERSION);
+        assert_eq!(stats.games_played, 4);
+        assert_eq!(stats.total_points, 30);
+        assert!(stats.by_opponent.is_empty());
+    }
+
+    #[test]
+    fn migrate_refuses_a_newer_version() {
+        let mut value = serde_json::json!({ "version": STATS_VERSION + 1 });
+        let error = Statistics::migrate(&mut value).unwrap_err();
+        assert!(error.contains("newer version"));
+    }
+
+    #[test]
+    fn load_from_reads_a_version_1_file() {
+        let path = scratch_dir("v1_stats").join(STATS_FILE);
+        fs::write(&path, V1_STATS).unwrap();
+
+        let stats = Statistics::load_from(&path);
+        assert_eq!(stats.version, STATS_VERSION);
+        assert_eq!(stats.games_won, 2);
+    }
+
+    #[test]
+    fn load_from_falls_back_to_the_backup() {
+        let path = scratch_dir("stats_backup").join(STATS_FILE);
+        fs::write(&path, "garbage").unwrap();
+        fs::write(storage::sibling(&path, "bak"), V1_STATS).unwrap();
+
+        let stats = Statistics::load_from(&path);
+        assert_eq!(stats.games_played, 4);
+    }
+
+    #[test]
+    fn save_to_leaves_a_newer_file_alone() {
+        let path = scratch_dir("stats_newer").join(STATS_FILE);
+        let newer = serde_json::json!({ "version": STATS_VERSION + 1 }).to_string();
+        fs::write(&path, &newer).unwrap();
+
+        let stats = Statistics::load_from(&path);
+        assert!(stats.save_to(&path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        assert!(!storage::sibling(&path, "corrupt").exists());
+    }
+
+    #[test]
+    fn differentials_start_from_the_first_game() {
+        let mut state = GameState::new(
+            1,
+            "Tit-for-Tat",
+            GameKind::PrisonersDilemma,
+            PayoffMatrix::CLASSIC,
+            0.0,
+            0,
+        );
+        state.computer_score = 5;
+        let mut stats = Statistics::new();
+        stats.record(&state);
+
+        assert_eq!(stats.best_score_differential, -5);
+        assert_eq!(stats.worst_score_differential, -5);
+        assert_eq!(stats.by_opponent[0].best_score_differential, -5);
+
+        state.player_score = 8;
+        stats.record(&state);
+        assert_eq!(stats.best_score_differential, 3);
+        assert_eq!(stats.worst_score_differential, -5);
+        assert_eq!(stats.by_opponent[0].best_score_differential, 3);
+        assert_eq!(stats.by_opponent[0].worst_score_differential, -5);
+    }
+
+    #[test]
+    fn load_from_starts_over_without_a_usable_file() {
+        let path = scratch_dir("stats_missing").join(STATS_FILE);
+        assert_eq!(Statistics::load_from(&path).games_played, 0);
+    }
+}
@@ -0,0 +1,602 @@
+//! Two players on different terminals, one hosting and one joining over TCP.
+//!
+//! The protocol is plain text, one message per line. The host referees: it
+//! holds the game settings, resolves every round and decides when an
+//! open-ended game stops.
+//!
+//! ```text
+//! joiner -> host   HELLO <version> <name>
+//! host -> joiner   RENAME               (the host has that name; HELLO again)
+//! host -> joiner   WELCOME <name>
+//! host -> joiner   GAME <game> <T> <R> <P> <S> <rounds> <continuation %>
+//!
+//! every round:
+//! joiner -> host   COMMIT <hash>
+//! host -> joiner   COMMIT <hash>
+//! joiner -> host   REVEAL <C|D> <nonce>
+//! host -> joiner   REVEAL <C|D> <nonce> <MORE|LAST>
+//!
+//! on a problem:    ERROR <message>
+//! now and then:    PING
+//! ```
+//!
+//! `<rounds>` is 0 for an open-ended game and `<continuation %>` is 0 for a
+//! fixed one. Closing the connection ends the game for both sides.
+//!
+//! Both sides send `PING`, which needs no answer, every half minute for as
+//! long as they are connected. A player may take all the time they want over
+//! a move, but a side that hasn't been heard from in two minutes is gone.
+//!
+//! Moves are committed before either is revealed, so whoever hears the other
+//! move first can no longer answer it. A commitment is the hex SHA-256 of
+//! `<name> <C|D> <nonce>`, where the nonce is fresh random hex every round.
+//! Hashing the name in stops a player from just echoing the other's
+//! commitment. A reveal that doesn't match ends the game.
+
+use crate::games::GameKind;
+use crate::hotseat::read_name;
+use crate::payoff::PayoffMatrix;
+use crate::{
+    animate_round_result, choose_game_and_payoffs, display_game_summary, get_player_move,
+    print_game_state, print_payoff_matrix, print_title, profile, read_length, GameState, Length,
+    Move,
+};
+use colored::Colorize;
+use sha2::{Digest, Sha256};
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 7878;
+const PROTOCOL_VERSION: u32 = 4;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// How long the other side can go without sending anything, pings included,
+/// before giving up on it.
+const READ_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn encode(m: Move) -> &'static str {
+    match m {
+        Move::Cooperate => "C",
+        Move::Defect => "D",
+    }
+}
+
+fn decode(text: &str) -> Result<Move, String> {
+    match text {
+        "C" => Ok(Move::Cooperate),
+        "D" => Ok(Move::Defect),
+        other => Err(format!("'{}' is not a move", other)),
+    }
+}
+
+/// A move that has been chosen and committed to but not revealed yet.
+struct Sealed {
+    choice: Move,
+    nonce: String,
+    commitment: String,
+}
+
+fn commitment(name: &str, choice: Move, nonce: &str) -> String {
+    Sha256::digest(format!("{} {} {}", name, encode(choice), nonce))
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn seal(name: &str, choice: Move) -> Sealed {
+    let nonce = format!("{:032x}", rand::random::<u128>());
+    Sealed {
+        commitment: commitment(name, choice, &nonce),
+        choice,
+        nonce,
+    }
+}
+
+/// One end of a game connection.
+struct Connection {
+    reader: BufReader<TcpStream>,
+    /// Shared with the thread sending pings, so their lines don't get mixed.
+    writer: Arc<Mutex<TcpStream>>,
+    /// Who is on the other end, for error messages.
+    peer: String,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Result<Connection, String> {
+        // Without a timeout, a peer that vanishes without closing the
+        // connection would leave this side waiting forever.
+        stream
+            .set_read_timeout(Some(READ_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        let reader = stream.try_clone().map_err(|e| e.to_string())?;
+        let writer = Arc::new(Mutex::new(stream));
+        keep_alive(Arc::downgrade(&writer));
+        Ok(Connection {
+            reader: BufReader::new(reader),
+            writer,
+            peer: "The other player".to_string(),
+        })
+    }
+
+    fn send(&mut self, message: &str) -> Result<(), String> {
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{}", message)
+            .and_then(|_| writer.flush())
+            .map_err(|_| format!("{} disconnected", self.peer))
+    }
+
+    /// Waits for the next message, which must be a `command`, and returns
+    /// what follows the command word.
+    fn expect(&mut self, command: &str) -> Result<String, String> {
+        self.expect_any(&[command]).map(|(_, rest)| rest)
+    }
+
+    /// Like `expect`, for a message that can be any of `commands`. Returns
+    /// the command word too.
+    fn expect_any<'a>(&mut self, commands: &[&'a str]) -> Result<(&'a str, String), String> {
+        let mut line = String::new();
+        while line.is_empty() || line.trim() == "PING" {
+            line.clear();
+            match self.reader.read_line(&mut line) {
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Err(format!(
+                        "{} stopped responding (nothing heard for {} minutes)",
+                        self.peer,
+                        READ_TIMEOUT.as_secs() / 60
+                    ))
+                }
+                Ok(0) | Err(_) => return Err(format!("{} disconnected", self.peer)),
+                Ok(_) => {}
+            }
+        }
+
+        let line = line.trim();
+        let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+        if let Some(&command) = commands.iter().find(|&&command| command == word) {
+            Ok((command, rest.to_string()))
+        } else if word == "ERROR" {
+            Err(format!("{} reported a problem: {}", self.peer, rest))
+        } else {
+            Err(format!("unexpected message from {}: '{}'", self.peer, line))
+        }
+    }
+
+    /// Checks a revealed `<C|D> <nonce>` against the commitment the other side
+    /// made earlier and returns the move.
+    fn open(&mut self, committed: &str, move_text: &str, nonce: &str) -> Result<Move, String> {
+        let choice = decode(move_text).map_err(|e| self.fail(e))?;
+        if commitment(&self.peer, choice, nonce) != committed {
+            return Err(self.fail(format!(
+                "{}'s revealed move doesn't match the one they committed to",
+                self.peer
+            )));
+        }
+        Ok(choice)
+    }
+
+    /// Tells the other side what went wrong before giving up.
+    fn fail(&mut self, error: String) -> String {
+        let _ = self.send(&format!("ERROR {}", error));
+        error
+    }
+}
+
+/// Pings the other side until the connection is dropped or fails.
+fn keep_alive(writer: Weak<Mutex<TcpStream>>) {
+    thread::spawn(move || loop {
+        thread::sleep(PING_INTERVAL);
+        let Some(writer) = writer.upgrade() else {
+            break;
+        };
+        let mut writer = writer.lock().unwrap();
+        if writeln!(writer, "PING")
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            break;
+        }
+    });
+}
+
+/// Parses `count` whitespace-separated fields of a message.
+fn fields(text: &str, count: usize) -> Result<Vec<&str>, String> {
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    if fields.len() == count {
+        Ok(fields)
+    } else {
+        Err(format!("malformed message '{}'", text))
+    }
+}
+
+fn number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.parse()
+        .map_err(|_| format!("'{}' is not a valid number", text))
+}
+
+fn wait_for_enter(prompt: &str) {
+    println!();
+    print!("{}: ", prompt.cyan());
+    io::stdout().flush().unwrap();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+/// Shows a failed game and waits, so the message isn't cleared away at once.
+fn report(result: Result<(), String>) {
+    if let Err(error) = result {
+        println!();
+        println!("{}", format!("[!] {}.", error).red());
+        wait_for_enter("Press Enter to return to menu");
+    }
+}
+
+/// Starts the next round by asking this side for its move.
+fn choose_move(state: &mut GameState) -> Move {
+    state.round += 1;
+    print_title();
+    print_game_state(state);
+
+    let own = get_player_move(state.game, false).expect("network games can't be saved");
+    println!();
+    println!(
+        "{}",
+        format!("Waiting for {}...", state.opponent).bright_black()
+    );
+    own
+}
+
+/// Shows and records a resolved round. `more` tells whether another follows.
+fn finish_round(state: &mut GameState, own: Move, other: Move, more: bool) {
+    let (own_points, other_points) = state.calculate_payoff(own, other);
+    print_title();
+    print_game_state(state);
+    animate_round_result(
+        state.game,
+        state.players(),
+        (own, other),
+        own,
+        other,
+        own_points,
+        other_points,
+    );
+
+    state.player_score += own_points;
+    state.computer_score += other_points;
+    state.history.push((own, other));
+    state.intended.push((own, other));
+    state.total_rounds = if more {
+        state.total_rounds.max(state.round + 1)
+    } else {
+        state.round
+    };
+}
+
+fn finish(state: &GameState) {
+    wait_for_enter("Press Enter to see the summary");
+    display_game_summary(state, None);
+    println!("{}", "═".repeat(60).bright_black());
+    wait_for_enter("Press Enter to return to menu");
+}
+
+/// Waits for someone to join on `port`, then referees a game against them.
+/// Anything left as `None` is asked for.
+pub fn host(port: u16, game: Option<GameKind>, length: Option<Length>) {
+    print_title();
+    let (game, payoffs) = choose_game_and_payoffs(game);
+    print_title();
+    print_payoff_matrix(&payoffs, game);
+    let length = length.unwrap_or_else(read_length);
+    report(run_host(port, game, payoffs, length));
+}
+
+fn run_host(
+    port: u16,
+    game: GameKind,
+    payoffs: PayoffMatrix,
+    length: Length,
+) -> Result<(), String> {
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .map_err(|e| format!("could not listen on port {} ({})", port, e))?;
+    println!();
+    println!(
+        "{}",
+        format!("Waiting for another player to join on port {}...", port)
+            .yellow()
+            .bold()
+    );
+    println!(
+        "{}",
+        format!(
+            "They can run: game_theory join --address <this computer's address>:{}",
+            port
+        )
+        .bright_black()
+    );
+    let (stream, _) = listener.accept().map_err(|e| e.to_string())?;
+    let mut connection = Connection::new(stream)?;
+
+    let name = loop {
+        let hello = connection.expect("HELLO")?;
+        let (version, name) = hello.split_once(' ').unwrap_or((&hello, ""));
+        if number::<u32>(version) != Ok(PROTOCOL_VERSION) {
+            return Err(connection.fail(format!(
+                "protocol version {} is not supported (expected {})",
+                version, PROTOCOL_VERSION
+            )));
+        }
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(connection.fail("a player needs a name".to_string()));
+        }
+        if !name.eq_ignore_ascii_case(&profile::active()) {
+            break name.to_string();
+        }
+        println!(
+            "{}",
+            format!(
+                "{} has your name too; waiting for them to pick another...",
+                name
+            )
+            .bright_black()
+        );
+        connection.send("RENAME")?;
+    };
+    connection.peer = name.clone();
+
+    let (rounds, continuation) = match length {
+        Length::Rounds(rounds) => (rounds, 0),
+        Length::Continuation(continuation) => (0, (continuation * 100.0).round() as u32),
+    };
+    connection.send(&format!("WELCOME {}", profile::active()))?;
+    connection.send(&format!(
+        "GAME {} {} {} {} {} {} {}",
+        game.slug(),
+        payoffs.temptation,
+        payoffs.reward,
+        payoffs.punishment,
+        payoffs.sucker,
+        rounds,
+        continuation
+    ))?;
+
+    let seed = rand::random();
+    let mut state = match length {
+        Length::Rounds(rounds) => GameState::new(rounds, &name, game, payoffs, 0.0, seed),
+        Length::Continuation(continuation) => {
+            GameState::open_ended(continuation, &name, game, payoffs, 0.0, seed)
+        }
+    };
+    state.player = Some(profile::active());
+
+    while state.round < state.total_rounds {
+        let own = seal(&profile::active(), choose_move(&mut state));
+        let committed = connection.expect("COMMIT")?;
+        connection.send(&format!("COMMIT {}", own.commitment))?;
+        let reveal = connection.expect("REVEAL")?;
+        let reveal = fields(&reveal, 2).map_err(|e| connection.fail(e))?;
+        let other = connection.open(&committed, reveal[0], reveal[1])?;
+
+        // Decide now whether the game goes on, so both sides hear it at once.
+        state.draw_continuation();
+        let more = state.round < state.total_rounds;
+        connection.send(&format!(
+            "REVEAL {} {} {}",
+            encode(own.choice),
+            own.nonce,
+            if more { "MORE" } else { "LAST" }
+        ))?;
+        finish_round(&mut state, own.choice, other, more);
+    }
+
+    finish(&state);
+    Ok(())
+}
+
+/// Connects to a host at `address` (`host:port`, asked for if `None`) and
+/// plays the game it sets up.
+pub fn join(address: Option<String>) {
+    print_title();
+    let address = address.unwrap_or_else(read_address);
+    report(run_join(&address));
+}
+
+fn read_address() -> String {
+    print!(
+        "{}: ",
+        format!(
+            "Host address (host or host:port, Enter for localhost:{})",
+            DEFAULT_PORT
+        )
+        .cyan()
+        .bold()
+    );
+    io::stdout().flush().unwrap();
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read line");
+    match input.trim() {
+        "" => format!("localhost:{}", DEFAULT_PORT),
+        address if address.contains(':') => address.to_string(),
+        host => format!("{}:{}", host, DEFAULT_PORT),
+    }
+}
+
+fn run_join(address: &str) -> Result<(), String> {
+    println!("{}", format!("Connecting to {}...", address).bright_black());
+    let target = address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addresses| addresses.next())
+        .ok_or_else(|| format!("could not find {}", address))?;
+    let stream = TcpStream::connect_timeout(&target, CONNECT_TIMEOUT)
+        .map_err(|e| format!("could not connect to {} ({})", address, e))?;
+    let mut connection = Connection::new(stream)?;
+
+    // What this side is called in the game, which is the profile's name
+    // unless the host has it too.
+    let mut own_name = profile::active();
+    connection.send(&format!("HELLO {} {}", PROTOCOL_VERSION, own_name))?;
+    let name = loop {
+        match connection.expect_any(&["WELCOME", "RENAME"])? {
+            ("WELCOME", name) => break name,
+            _ => {
+                println!(
+                    "{}",
+                    format!("The host is also called {}.", own_name).yellow()
+                );
+                own_name = read_name("Your name for this game", "Player 2", Some(&own_name));
+                connection.send(&format!("HELLO {} {}", PROTOCOL_VERSION, own_name))?;
+            }
+        }
+    };
+    connection.peer = name.clone();
+
+    let settings = connection.expect("GAME")?;
+    let settings = fields(&settings, 7).map_err(|e| connection.fail(e))?;
+    let game = GameKind::from_slug(settings[0])
+        .ok_or_else(|| connection.fail(format!("unknown game '{}'", settings[0])))?;
+    let payoffs = PayoffMatrix {
+        temptation: number(settings[1]).map_err(|e| connection.fail(e))?,
+        reward: number(settings[2]).map_err(|e| connection.fail(e))?,
+        punishment: number(settings[3]).map_err(|e| connection.fail(e))?,
+        sucker: number(settings[4]).map_err(|e| connection.fail(e))?,
+    };
+    let rounds: u32 = number(settings[5]).map_err(|e| connection.fail(e))?;
+    let continuation: u32 = number(settings[6]).map_err(|e| connection.fail(e))?;
+    // A fixed game has no continuation, an open-ended one needs a chance below 100%.
+    let valid = match rounds {
+        0 => (1..=99).contains(&continuation),
+        _ => continuation == 0,
+    };
+    if !valid {
+        return Err(connection.fail(format!(
+            "invalid game length ({} rounds, {}% continuation)",
+            rounds, continuation
+        )));
+    }
+
+    let mut state = if rounds > 0 {
+        GameState::new(rounds, &name, game, payoffs, 0.0, 0)
+    } else {
+        GameState::open_ended(continuation as f64 / 100.0, &name, game, payoffs, 0.0, 0)
+    };
+    state.player = Some(own_name.clone());
+
+    print_title();
+    println!(
+        "{}",
+        format!("Connected! You are playing {}.", name)
+            .bright_green()
+            .bold()
+    );
+    println!();
+    print_payoff_matrix(&payoffs, game);
+    wait_for_enter("Press Enter to start");
+
+    while state.round < state.total_rounds {
+        let own = seal(&own_name, choose_move(&mut state));
+        connection.send(&format!("COMMIT {}", own.commitment))?;
+        let committed = connection.expect("COMMIT")?;
+        connection.send(&format!("REVEAL {} {}", encode(own.choice), own.nonce))?;
+        let reveal = connection.expect("REVEAL")?;
+        let reveal = fields(&reveal, 3).map_err(|e| connection.fail(e))?;
+        let other = connection.open(&committed, reveal[0], reveal[1])?;
+        let more = match reveal[2] {
+            "MORE" => true,
+            "LAST" => false,
+            other => return Err(connection.fail(format!("'{}' is not MORE or LAST", other))),
+        };
+        finish_round(&mut state, own.choice, other, more);
+    }
+
+    finish(&state);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Both ends of a connection over the loopback interface, the first one
+    /// playing against "Bob" and the second against "Alice".
+    fn pair() -> (Connection, Connection) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        let mut host = Connection::new(server).unwrap();
+        let mut joiner = Connection::new(client).unwrap();
+        host.peer = "Bob".to_string();
+        joiner.peer = "Alice".to_string();
+        (host, joiner)
+    }
+
+    #[test]
+    fn commitment_covers_name_move_and_nonce() {
+        let base = commitment("Bob", Move::Defect, "00ff");
+        assert_eq!(base, commitment("Bob", Move::Defect, "00ff"));
+        assert_eq!(base.len(), 64);
+        assert_ne!(base, commitment("Alice", Move::Defect, "00ff"));
+        assert_ne!(base, commitment("Bob", Move::Cooperate, "00ff"));
+        assert_ne!(base, commitment("Bob", Move::Defect, "00fe"));
+    }
+
+    #[test]
+    fn seal_uses_a_fresh_nonce() {
+        assert_ne!(
+            seal("Bob", Move::Defect).nonce,
+            seal("Bob", Move::Defect).nonce
+        );
+    }
+
+    #[test]
+    fn open_accepts_a_matching_reveal() {
+        let (mut host, _joiner) = pair();
+        let sealed = seal("Bob", Move::Defect);
+        assert_eq!(
+            host.open(&sealed.commitment, "D", &sealed.nonce),
+            Ok(Move::Defect)
+        );
+    }
+
+    #[test]
+    fn open_rejects_a_changed_move() {
+        let (mut host, mut joiner) = pair();
+        let sealed = seal("Bob", Move::Defect);
+
+        let error = host
+            .open(&sealed.commitment, "C", &sealed.nonce)
+            .unwrap_err();
+        assert!(error.contains("doesn't match"));
+        // The cheater is told why the game stopped.
+        let reported = joiner.expect("REVEAL").unwrap_err();
+        assert!(reported.contains("Alice reported a problem"));
+    }
+
+    #[test]
+    fn open_rejects_a_copied_commitment() {
+        let (mut host, _joiner) = pair();
+        // Bob echoes Alice's commitment, then her reveal once he has seen it.
+        let alice = seal("Alice", Move::Cooperate);
+        assert!(host.open(&alice.commitment, "C", &alice.nonce).is_err());
+    }
+
+    #[test]
+    fn expect_skips_pings() {
+        let (mut host, mut joiner) = pair();
+        joiner.send("PING").unwrap();
+        joiner.send("COMMIT abc").unwrap();
+        assert_eq!(host.expect("COMMIT"), Ok("abc".to_string()));
+    }
+
+    #[test]
+    fn open_rejects_something_that_is_not_a_move() {
+        let (mut host, _joiner) = pair();
+        let sealed = seal("Bob", Move::Cooperate);
+        assert!(host.open(&sealed.commitment, "X", &sealed.nonce).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::Move;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const PAYOFF_FILE: &str = "payoff_matrix.json";
+
+/// Points for each outcome, always from the point of view of the row player.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PayoffMatrix {
+    pub temptation: i32,
+    pub reward: i32,
+    pub punishment: i32,
+    pub sucker: i32,
+}
+
+impl PayoffMatrix {
+    pub const CLASSIC: PayoffMatrix = PayoffMatrix {
+        temptation: 5,
+        reward: 3,
+        punishment: 1,
+        sucker: 0,
+    };
+
+    pub fn payoff(&self, player_move: Move, computer_move: Move) -> (i32, i32) {
+        match (player_move, computer_move) {
+            (Move::Cooperate, Move::Cooperate) => (self.reward, self.reward),
+            (Move::Cooperate, Move::Defect) => (self.sucker, self.temptation),
+            (Move::Defect, Move::Cooperate) => (self.temptation, self.sucker),
+            (Move::Defect, Move::Defect) => (self.punishment, self.punishment),
+        }
+    }
+
+    /// Conditions that make this a Prisoner's Dilemma and that this matrix breaks.
+    pub fn dilemma_violations(&self) -> Vec<&'static str> {
+        let mut violations = Vec::new();
+        if self.temptation <= self.reward {
+            violations.push("T > R: defecting against a cooperator should pay more than cooperating");
+        }
+        if self.reward <= self.punishment {
+            violations.push("R > P: mutual cooperation should beat mutual defection");
+        }
+        if self.punishment <= self.sucker {
+            violations.push("P > S: being exploited should be the worst outcome");
+        }
+        if 2 * self.reward <= self.temptation + self.sucker {
+            violations.push("2R > T + S: taking turns exploiting each other should not beat cooperating");
+        }
+        violations
+    }
+
+    /// Reads `payoff_matrix.json` from the working directory, if present.
+    pub fn from_config() -> Option<PayoffMatrix> {
+        if !Path::new(PAYOFF_FILE).exists() {
+            return None;
+        }
+        match fs::read_to_string(PAYOFF_FILE).map(|content| serde_json::from_str(&content)) {
+            Ok(Ok(matrix)) => Some(matrix),
+            _ => {
+                println!(
+                    "{}",
+                    format!("[!] Could not parse {}; ignoring it.", PAYOFF_FILE).red()
+                );
+                None
+            }
+        }
+    }
+
+    /// The matrix used when the player doesn't pick one: the config file, else the classic one.
+    pub fn default_matrix() -> PayoffMatrix {
+        PayoffMatrix::from_config().unwrap_or(PayoffMatrix::CLASSIC)
+    }
+
+    pub fn print_warnings(&self) {
+        for violation in self.dilemma_violations() {
+            println!(
+                "  {} {}",
+                "[!] Not a Prisoner's Dilemma:".yellow().bold(),
+                violation.yellow()
+            );
+        }
+    }
+}
+
+pub fn presets() -> Vec<(&'static str, PayoffMatrix)> {
+    vec![
+        ("Classic (Axelrod)", PayoffMatrix::CLASSIC),
+        (
+            "Cheap Betrayal",
+            PayoffMatrix {
+                temptation: 4,
+                reward: 3,
+                punishment: 1,
+                sucker: 0,
+            },
+        ),
+        (
+            "High Stakes",
+            PayoffMatrix {
+                temptation: 10,
+                reward: 7,
+                punishment: 2,
+                sucker: 0,
+            },
+        ),
+        (
+            "Harsh Punishment",
+            PayoffMatrix {
+                temptation: 5,
+                reward: 3,
+                punishment: -1,
+                sucker: -3,
+            },
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(temptation: i32, reward: i32, punishment: i32, sucker: i32) -> PayoffMatrix {
+        PayoffMatrix {
+            temptation,
+            reward,
+            punishment,
+            sucker,
+        }
+    }
+
+    #[test]
+    fn classic_matrix_is_a_dilemma() {
+        assert!(PayoffMatrix::CLASSIC.dilemma_violations().is_empty());
+    }
+
+    #[test]
+    fn each_condition_is_reported() {
+        let cases = [
+            (matrix(3, 3, 1, 0), "T > R"),
+            (matrix(3, 2, 2, 0), "R > P"),
+            (matrix(5, 3, 0, 0), "P > S"),
+            (matrix(10, 3, 1, 0), "2R > T + S"),
+        ];
+        for (matrix, condition) in cases {
+            let violations = matrix.dilemma_violations();
+            assert_eq!(violations.len(), 1, "{:?}", violations);
+            assert!(violations[0].starts_with(condition), "{:?}", violations);
+        }
+    }
+
+    #[test]
+    fn a_flat_matrix_breaks_every_condition() {
+        assert_eq!(matrix(0, 0, 0, 0).dilemma_violations().len(), 4);
+    }
+}
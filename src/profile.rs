@@ -0,0 +1,406 @@
+use crate::{storage, warn};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const PROFILES_FILE: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+const DEFAULT_PROFILE: &str = "Player";
+pub const MAX_NAME_LENGTH: usize = 20;
+/// Overrides where profiles and statistics are kept.
+pub const DATA_DIR_ENV: &str = "GAME_THEORY_DATA_DIR";
+const APP_DIR: &str = "game_theory";
+
+/// Files that lived in the working directory before profiles existed.
+const LEGACY_FILES: [&str; 3] = ["game_stats.json", "game_history.json", "saved_game.json"];
+
+static ACTIVE: Mutex<String> = Mutex::new(String::new());
+static DATA_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    active: String,
+    profiles: Vec<String>,
+}
+
+impl Registry {
+    fn load() -> Self {
+        let path = data_dir().join(PROFILES_FILE);
+        if path.exists() {
+            if let Ok(content) = fs::read_to_string(path) {
+                if let Ok(registry) = serde_json::from_str(&content) {
+                    return registry;
+                }
+            }
+        }
+        Registry::default()
+    }
+
+    fn save(&self) {
+        let path = data_dir().join(PROFILES_FILE);
+        let written = serde_json::to_string_pretty(self)
+            .map_err(|e| e.to_string())
+            .and_then(|json| storage::write_atomic(&path, &json).map_err(|e| e.to_string()));
+        if let Err(error) = written {
+            warn(&format!("Could not save {}: {}.", path.display(), error));
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.profiles
+            .iter()
+            .position(|p| p.eq_ignore_ascii_case(name))
+    }
+}
+
+fn directory(name: &str) -> PathBuf {
+    let folder: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    data_dir().join(PROFILES_DIR).join(folder)
+}
+
+/// Keeps all data in `dir` (from `--data-dir`) instead of the default location.
+pub fn set_data_dir(dir: PathBuf) {
+    *DATA_DIR.lock().unwrap() = Some(dir);
+}
+
+/// Where profiles and statistics live: `--data-dir`, then `$GAME_THEORY_DATA_DIR`,
+/// then the platform's data directory.
+pub fn data_dir() -> PathBuf {
+    DATA_DIR
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| match env::var_os(DATA_DIR_ENV) {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => default_data_dir(),
+        })
+        .clone()
+}
+
+/// `$XDG_DATA_HOME/game_theory`, or `~/.local/share/game_theory` when that isn't
+/// set (`%APPDATA%\game_theory` on Windows). Falls back to the working directory.
+fn default_data_dir() -> PathBuf {
+    let xdg = env::var_os("XDG_DATA_HOME").map(PathBuf::from);
+    if let Some(dir) = xdg.filter(|dir| dir.is_absolute()) {
+        return dir.join(APP_DIR);
+    }
+    if cfg!(windows) {
+        if let Some(dir) = env::var_os("APPDATA") {
+            return PathBuf::from(dir).join(APP_DIR);
+        }
+    }
+    match env::var_os("HOME") {
+        Some(home) if !home.is_empty() => PathBuf::from(home)
+            .join(".local")
+            .join("share")
+            .join(APP_DIR),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Older versions kept their profiles in the working directory. Copies them to
+/// the data directory and reports whether there were any.
+fn import_profiles() -> bool {
+    let root = data_dir();
+    let old_registry = Path::new(PROFILES_FILE);
+    let same_place = fs::canonicalize(&root).ok() == fs::canonicalize(".").ok();
+    if same_place || !old_registry.exists() {
+        return false;
+    }
+
+    if let Ok(folders) = fs::read_dir(PROFILES_DIR) {
+        for folder in folders.flatten().filter(|f| f.path().is_dir()) {
+            let target = root.join(PROFILES_DIR).join(folder.file_name());
+            if fs::create_dir_all(&target).is_err() {
+                continue;
+            }
+            for file in fs::read_dir(folder.path()).into_iter().flatten().flatten() {
+                let _ = fs::copy(file.path(), target.join(file.file_name()));
+            }
+        }
+    }
+    fs::copy(old_registry, root.join(PROFILES_FILE)).is_ok()
+}
+
+/// The profile folders already in the data directory, named after the folders.
+fn existing_profiles() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(data_dir().join(PROFILES_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
+
+fn set_active(registry: &mut Registry, name: &str) {
+    registry.active = name.to_string();
+    registry.save();
+    *ACTIVE.lock().unwrap() = name.to_string();
+}
+
+/// Checks a new name for a profile, or for the profile called `current` when
+/// renaming one.
+fn validate_name(registry: &Registry, name: &str, current: Option<&str>) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("A profile name can't be empty.".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!(
+            "A profile name can be at most {} characters.",
+            MAX_NAME_LENGTH
+        ));
+    }
+    let others = registry
+        .profiles
+        .iter()
+        .filter(|p| Some(p.as_str()) != current);
+    for other in others {
+        if other.eq_ignore_ascii_case(name) {
+            return Err(format!("A profile called '{}' already exists.", name));
+        }
+        // Folder names keep only letters and digits.
+        if directory(other) == directory(name) {
+            return Err(format!(
+                "'{}' is too similar to the profile '{}'; only letters and digits tell names apart.",
+                name, other
+            ));
+        }
+    }
+    let own_folder = current.is_some_and(|current| {
+        current.eq_ignore_ascii_case(name) || directory(current) == directory(name)
+    });
+    if !own_folder && directory(name).exists() {
+        return Err(format!("A profile called '{}' already exists.", name));
+    }
+    Ok(())
+}
+
+/// Makes sure a profile is active. On first run this imports data left in the
+/// working directory by older versions, or creates the default profile.
+pub fn ensure_active() -> Result<(), String> {
+    let root = data_dir();
+    fs::create_dir_all(&root)
+        .map_err(|e| format!("Could not create the data folder {}: {}", root.display(), e))?;
+
+    let mut registry = Registry::load();
+    if registry.profiles.is_empty() && import_profiles() {
+        registry = Registry::load();
+    }
+    if registry.profiles.is_empty() {
+        // A missing or damaged registry must not orphan the folders it listed.
+        registry.profiles = existing_profiles();
+        if registry.find(DEFAULT_PROFILE).is_some() {
+            registry.active = DEFAULT_PROFILE.to_string();
+        }
+    }
+    if registry.profiles.is_empty() {
+        create(DEFAULT_PROFILE)?;
+        let dir = directory(DEFAULT_PROFILE);
+        for file in LEGACY_FILES {
+            if Path::new(file).exists() {
+                let _ = fs::copy(file, dir.join(file));
+            }
+        }
+        return Ok(());
+    }
+
+    let active = match registry.find(&registry.active) {
+        Some(i) => registry.profiles[i].clone(),
+        None => registry.profiles[0].clone(),
+    };
+    set_active(&mut registry, &active);
+    Ok(())
+}
+
+pub fn active() -> String {
+    ACTIVE.lock().unwrap().clone()
+}
+
+pub fn names() -> Vec<String> {
+    Registry::load().profiles
+}
+
+/// Where the active profile keeps `file`.
+pub fn data_path(file: &str) -> PathBuf {
+    directory(&active()).join(file)
+}
+
+/// Where `profile` keeps `file`, for reading other players' data.
+pub fn data_path_for(profile: &str, file: &str) -> PathBuf {
+    directory(profile).join(file)
+}
+
+/// Creates a profile and makes it the active one.
+pub fn create(name: &str) -> Result<(), String> {
+    let name = name.trim();
+    let mut registry = Registry::load();
+    validate_name(&registry, name, None)?;
+
+    fs::create_dir_all(directory(name))
+        .map_err(|e| format!("Could not create the profile folder: {}", e))?;
+    registry.profiles.push(name.to_string());
+    set_active(&mut registry, name);
+    Ok(())
+}
+
+pub fn switch(name: &str) -> Result<(), String> {
+    let mut registry = Registry::load();
+    let index = registry
+        .find(name)
+        .ok_or_else(|| format!("There is no profile called '{}'.", name))?;
+    let name = registry.profiles[index].clone();
+    set_active(&mut registry, &name);
+    Ok(())
+}
+
+pub fn rename(old: &str, new: &str) -> Result<(), String> {
+    let new = new.trim();
+    let mut registry = Registry::load();
+    let index = registry
+        .find(old)
+        .ok_or_else(|| format!("There is no profile called '{}'.", old))?;
+    let old = registry.profiles[index].clone();
+    validate_name(&registry, new, Some(&old))?;
+
+    if directory(&old) != directory(new) {
+        fs::rename(directory(&old), directory(new))
+            .map_err(|e| format!("Could not rename the profile folder: {}", e))?;
+    }
+    registry.profiles[index] = new.to_string();
+    if registry.active == old {
+        set_active(&mut registry, new);
+    } else {
+        registry.save();
+    }
+    Ok(())
+}
+
+/// Deletes a profile and all of its data. If it was active, the first
+/// remaining profile takes over; deleting the last profile is refused.
+pub fn delete(name: &str) -> Result<(), String> {
+    let mut registry = Registry::load();
+    let index = registry
+        .find(name)
+        .ok_or_else(|| format!("There is no profile called '{}'.", name))?;
+    if registry.profiles.len() == 1 {
+        return Err("You can't delete the only profile.".to_string());
+    }
+
+    // The folder goes first, so a profile that couldn't be deleted is still listed.
+    match fs::remove_dir_all(directory(&registry.profiles[index])) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            return Err(format!("Could not delete the profile folder: {}", e));
+        }
+        _ => {}
+    }
+    let name = registry.profiles.remove(index);
+    if registry.active == name {
+        let next = registry.profiles[0].clone();
+        set_active(&mut registry, &next);
+    } else {
+        registry.save();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::MutexGuard;
+
+    /// The data directory is shared by the whole process, so tests using it
+    /// take turns.
+    static DATA_DIR_IN_USE: Mutex<()> = Mutex::new(());
+
+    /// Points the data directory at an empty folder of its own until the
+    /// returned guard is dropped.
+    fn scratch_data_dir(name: &str) -> (MutexGuard<'static, ()>, PathBuf) {
+        let guard = DATA_DIR_IN_USE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let dir = env::temp_dir().join(format!(
+            "game_theory_profiles_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        set_data_dir(dir.clone());
+        (guard, dir)
+    }
+
+    #[test]
+    fn create_makes_the_folder_and_switches_to_it() {
+        let _dir = scratch_data_dir("create");
+        create("Ann").unwrap();
+        create("Bob").unwrap();
+
+        assert_eq!(active(), "Bob");
+        assert_eq!(names(), ["Ann", "Bob"]);
+        assert!(directory("Ann").is_dir());
+        assert!(create("ann").is_err());
+    }
+
+    #[test]
+    fn create_refuses_names_sharing_a_folder() {
+        let _dir = scratch_data_dir("collide");
+        create("a b").unwrap();
+
+        let error = create("a_b").unwrap_err();
+        assert!(error.contains("too similar to the profile 'a b'"));
+        assert_eq!(names(), ["a b"]);
+    }
+
+    #[test]
+    fn rename_moves_the_folder() {
+        let _dir = scratch_data_dir("rename");
+        create("Ann").unwrap();
+        fs::write(data_path("game_stats.json"), "{}").unwrap();
+        create("Bob").unwrap();
+
+        rename("Ann", "Cleo").unwrap();
+        assert_eq!(names(), ["Cleo", "Bob"]);
+        assert!(data_path_for("Cleo", "game_stats.json").exists());
+        assert!(!directory("Ann").exists());
+
+        // Only the spelling changes; the folder stays the same.
+        rename("Cleo", "CLEO").unwrap();
+        rename("Bob", "Bob!").unwrap();
+        assert_eq!(names(), ["CLEO", "Bob!"]);
+        assert_eq!(active(), "Bob!");
+    }
+
+    #[test]
+    fn delete_removes_the_folder_and_switches_away() {
+        let _dir = scratch_data_dir("delete");
+        create("Ann").unwrap();
+        create("Bob").unwrap();
+
+        delete("Bob").unwrap();
+        assert_eq!(names(), ["Ann"]);
+        assert_eq!(active(), "Ann");
+        assert!(!directory("Bob").exists());
+        assert!(delete("Ann").is_err());
+    }
+
+    #[test]
+    fn ensure_active_rebuilds_a_damaged_registry() {
+        let (_guard, dir) = scratch_data_dir("rebuild");
+        for folder in ["Player", "Ann"] {
+            fs::create_dir_all(dir.join(PROFILES_DIR).join(folder)).unwrap();
+        }
+        fs::write(dir.join(PROFILES_FILE), "garbage").unwrap();
+
+        ensure_active().unwrap();
+        assert_eq!(names(), ["Ann", "Player"]);
+        assert_eq!(active(), "Player");
+    }
+}
@@ -0,0 +1,115 @@
+use crate::history::GameRecord;
+use crate::{print_game_state, print_round_result, print_title, read_number, GameState};
+use colored::Colorize;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+/// The game as it stood when `round` (1-based) was about to be played.
+fn state_before(record: &GameRecord, round: usize) -> GameState {
+    let mut state = record.to_state();
+    state.round = round as u32;
+    state.history.truncate(round - 1);
+    state.intended.truncate(round - 1);
+    state.player_score = record.rounds[..round - 1]
+        .iter()
+        .map(|r| r.player_points)
+        .sum();
+    state.computer_score = record.rounds[..round - 1]
+        .iter()
+        .map(|r| r.computer_points)
+        .sum();
+    state
+}
+
+fn show_round(record: &GameRecord, number: usize, round: usize) {
+    let played = &record.rounds[round - 1];
+
+    print_title();
+    println!(
+        "{}",
+        format!(
+            "REPLAY - Game #{} vs {} ({}), played {} UTC",
+            number,
+            record.opponent,
+            record.game.name(),
+            record.date()
+        )
+        .yellow()
+        .bold()
+    );
+    print_game_state(&state_before(record, round));
+    print_round_result(
+        record.game,
+        None,
+        played.intended(),
+        played.player_move,
+        played.computer_move,
+        played.player_points,
+        played.computer_points,
+    );
+    println!();
+}
+
+/// Steps through a recorded game with the same rendering used while playing.
+pub fn run(record: &GameRecord, number: usize) {
+    if record.rounds.is_empty() {
+        return;
+    }
+
+    let total = record.rounds.len();
+    let mut round = 1;
+
+    loop {
+        show_round(record, number, round);
+
+        print!(
+            "{}: ",
+            "[N]ext  [P]revious  [A]uto-play  [Q]uit".cyan().bold()
+        );
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        match input.trim().to_lowercase().as_str() {
+            "" | "n" => {
+                if round < total {
+                    round += 1;
+                } else {
+                    println!("{}", "That was the last round.".yellow());
+                    thread::sleep(Duration::from_millis(800));
+                }
+            }
+            "p" => {
+                if round > 1 {
+                    round -= 1;
+                } else {
+                    println!("{}", "This is the first round.".yellow());
+                    thread::sleep(Duration::from_millis(800));
+                }
+            }
+            "a" => {
+                let speed = read_number("Speed: 1 slow, 2 normal, 3 fast", 1, 3, 2);
+                let pause = match speed {
+                    1 => Duration::from_millis(3000),
+                    2 => Duration::from_millis(1500),
+                    _ => Duration::from_millis(500),
+                };
+                while round < total {
+                    thread::sleep(pause);
+                    round += 1;
+                    show_round(record, number, round);
+                }
+                thread::sleep(pause);
+            }
+            "q" => return,
+            _ => {
+                println!("{}", "[!] Please enter N, P, A or Q.".red());
+                thread::sleep(Duration::from_millis(800));
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::profile;
+use crate::storage;
+use crate::strategy::{self, Strategy};
+use crate::GameState;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::fs;
+
+const SAVE_FILE: &str = "saved_game.json";
+
+pub fn save(state: &GameState) -> Result<(), String> {
+    let path = profile::data_path(SAVE_FILE);
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    storage::write_atomic(&path, &json)
+        .map_err(|e| format!("could not write {}: {}", path.display(), e))
+}
+
+pub fn load() -> Option<GameState> {
+    let path = profile::data_path(SAVE_FILE);
+    if !path.exists() {
+        return None;
+    }
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn delete() {
+    let _ = fs::remove_file(profile::data_path(SAVE_FILE));
+}
+
+/// Rebuilds the computer opponent of a saved game. Strategies only keep state
+/// derived from the moves they have seen, so feeding them the recorded history
+/// brings them back to where they were. A throwaway RNG is used so the saved
+/// game's own RNG stays untouched.
+pub fn restore_opponent(state: &GameState) -> Option<Box<dyn Strategy>> {
+    let mut opponent = strategy::find(&state.opponent)?;
+    let mut scratch = ChaCha8Rng::seed_from_u64(0);
+    opponent.resume(&state.history, &mut scratch);
+    Some(opponent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::GameKind;
+    use crate::payoff::PayoffMatrix;
+    use crate::Move::{self, Cooperate, Defect};
+
+    fn saved_game(opponent: &str, history: &[(Move, Move)]) -> GameState {
+        let mut state = GameState::new(
+            10,
+            opponent,
+            GameKind::PrisonersDilemma,
+            PayoffMatrix::CLASSIC,
+            0.0,
+            7,
+        );
+        state.round = history.len() as u32;
+        state.history = history.to_vec();
+        state
+    }
+
+    #[test]
+    fn restored_opponent_picks_up_where_it_left_off() {
+        // Gradual answered the player's second defection with the first of
+        // two punishing defections; the second one is still owed.
+        let state = saved_game(
+            "Gradual",
+            &[
+                (Cooperate, Cooperate),
+                (Defect, Cooperate),
+                (Cooperate, Defect),
+                (Cooperate, Cooperate),
+                (Defect, Cooperate),
+                (Cooperate, Defect),
+            ],
+        );
+        let mut opponent = restore_opponent(&state).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert_eq!(opponent.next_move(&state.history, &mut rng), Defect);
+
+        // Without the replay it would have forgotten and cooperated.
+        let mut fresh = strategy::find("Gradual").unwrap();
+        assert_eq!(fresh.next_move(&state.history, &mut rng), Cooperate);
+    }
+
+    #[test]
+    fn unknown_opponent_cannot_be_restored() {
+        assert!(restore_opponent(&saved_game("Nobody", &[])).is_none());
+    }
+}
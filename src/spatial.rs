@@ -0,0 +1,345 @@
+use crate::chart;
+use crate::evolution;
+use crate::payoff::PayoffMatrix;
+use crate::strategy::{self, Strategy};
+use crate::tournament;
+use colored::Colorize;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_SIZE: u32 = 30;
+pub const MAX_SIZE: u32 = 60;
+pub const DEFAULT_GENERATIONS: u32 = 50;
+/// A single round per encounter, as in Nowak and May's original model.
+pub const DEFAULT_ROUNDS: u32 = 1;
+/// Unconditional cooperators against unconditional defectors.
+pub const DEFAULT_STRATEGIES: [&str; 2] = ["Always Cooperate", "Always Defect"];
+/// Each strategy needs its own color on the grid.
+pub const MAX_STRATEGIES: usize = chart::PALETTE.len();
+const FRAME_DELAY: Duration = Duration::from_millis(150);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Neighborhood {
+    /// The four cells sharing an edge.
+    VonNeumann,
+    /// The eight cells sharing an edge or a corner.
+    Moore,
+}
+
+impl Neighborhood {
+    pub const ALL: [Neighborhood; 2] = [Neighborhood::VonNeumann, Neighborhood::Moore];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Neighborhood::VonNeumann => "von Neumann",
+            Neighborhood::Moore => "Moore",
+        }
+    }
+
+    pub fn neighbors(self) -> usize {
+        self.offsets().len()
+    }
+
+    pub fn slug(self) -> &'static str {
+        match self {
+            Neighborhood::VonNeumann => "von-neumann",
+            Neighborhood::Moore => "moore",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Neighborhood> {
+        Neighborhood::ALL.into_iter().find(|n| n.slug() == slug)
+    }
+
+    fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            Neighborhood::VonNeumann => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighborhood::Moore => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+
+    /// One offset out of every opposite pair, so each neighboring pair of
+    /// cells is visited once.
+    fn forward(self) -> &'static [(i32, i32)] {
+        match self {
+            Neighborhood::VonNeumann => &[(1, 0), (0, 1)],
+            Neighborhood::Moore => &[(1, 0), (-1, 1), (0, 1), (1, 1)],
+        }
+    }
+}
+
+pub struct Settings {
+    /// Cells along each side of the square grid.
+    pub size: u32,
+    pub neighborhood: Neighborhood,
+    /// Names of the strategies on the grid; `DEFAULT_STRATEGIES` if empty.
+    pub strategies: Vec<String>,
+    /// Relative share of each strategy in the starting grid; equal if empty.
+    pub mix: Vec<f64>,
+    pub generations: u32,
+    pub rounds: u32,
+    pub payoffs: PayoffMatrix,
+    pub seed: u64,
+}
+
+/// A toroidal grid where every cell holds the index of a strategy.
+struct Grid {
+    names: Vec<String>,
+    size: usize,
+    neighborhood: Neighborhood,
+    cells: Vec<usize>,
+    /// Cells that switched strategy in the last generation.
+    changed: Vec<bool>,
+    // Two instances of each strategy, so a strategy can meet itself.
+    players: Vec<Box<dyn Strategy>>,
+    opponents: Vec<Box<dyn Strategy>>,
+    rounds: u32,
+    payoffs: PayoffMatrix,
+    rng: ChaCha8Rng,
+}
+
+impl Grid {
+    fn new(settings: &Settings) -> Grid {
+        let names: Vec<String> = if settings.strategies.is_empty() {
+            DEFAULT_STRATEGIES.iter().map(|s| s.to_string()).collect()
+        } else {
+            settings.strategies.clone()
+        };
+        let instances = || -> Vec<Box<dyn Strategy>> {
+            names
+                .iter()
+                .map(|n| strategy::find(n).expect("validated strategy name"))
+                .collect()
+        };
+        let mix = if settings.mix.iter().sum::<f64>() > 0.0 {
+            settings.mix.clone()
+        } else {
+            vec![1.0; names.len()]
+        };
+
+        let mut rng = ChaCha8Rng::seed_from_u64(settings.seed);
+        let size = settings.size as usize;
+        let cells = (0..size * size)
+            .map(|_| evolution::pick(&mix, &mut rng))
+            .collect();
+
+        Grid {
+            players: instances(),
+            opponents: instances(),
+            names,
+            size,
+            neighborhood: settings.neighborhood,
+            cells,
+            changed: vec![false; size * size],
+            rounds: settings.rounds,
+            payoffs: settings.payoffs,
+            rng,
+        }
+    }
+
+    /// The cell at `offset` from `cell`, wrapping around the edges.
+    fn neighbor(&self, cell: usize, (dx, dy): (i32, i32)) -> usize {
+        let size = self.size as i32;
+        let x = (cell as i32 % size + dx).rem_euclid(size);
+        let y = (cell as i32 / size + dy).rem_euclid(size);
+        (y * size + x) as usize
+    }
+
+    /// Points every cell earns this generation, from one match against each
+    /// neighbor and one against itself.
+    fn scores(&mut self) -> Vec<i64> {
+        let mut scores = vec![0i64; self.cells.len()];
+        for cell in 0..self.cells.len() {
+            let own = self.cells[cell];
+            let state = tournament::play_match(
+                self.players[own].as_mut(),
+                self.opponents[own].as_mut(),
+                self.rounds,
+                self.payoffs,
+                0.0,
+                self.rng.gen(),
+            );
+            scores[cell] += state.player_score as i64;
+
+            for &offset in self.neighborhood.forward() {
+                let other = self.neighbor(cell, offset);
+                let state = tournament::play_match(
+                    self.players[own].as_mut(),
+                    self.opponents[self.cells[other]].as_mut(),
+                    self.rounds,
+                    self.payoffs,
+                    0.0,
+                    self.rng.gen(),
+                );
+                scores[cell] += state.player_score as i64;
+                scores[other] += state.computer_score as i64;
+            }
+        }
+        scores
+    }
+
+    /// Every cell takes on the strategy of the best scorer among itself and
+    /// its neighbors, all at once. Returns how many cells switched.
+    fn step(&mut self) -> usize {
+        let scores = self.scores();
+        let next: Vec<usize> = (0..self.cells.len())
+            .map(|cell| {
+                // Ties go to the cell's own strategy.
+                let mut best = cell;
+                for &offset in self.neighborhood.offsets() {
+                    let other = self.neighbor(cell, offset);
+                    if scores[other] > scores[best] {
+                        best = other;
+                    }
+                }
+                self.cells[best]
+            })
+            .collect();
+
+        for (changed, (old, new)) in self.changed.iter_mut().zip(self.cells.iter().zip(&next)) {
+            *changed = old != new;
+        }
+        self.cells = next;
+        self.changed.iter().filter(|&&c| c).count()
+    }
+
+    fn shares(&self) -> Vec<f64> {
+        let mut shares = vec![0.0; self.names.len()];
+        for &cell in &self.cells {
+            shares[cell] += 1.0 / self.cells.len() as f64;
+        }
+        shares
+    }
+}
+
+fn print_grid(grid: &Grid, generation: u32, generations: u32) {
+    crate::clear_screen();
+    println!(
+        "{}",
+        format!(
+            "SPATIAL PRISONER'S DILEMMA - Generation {}/{}",
+            generation, generations
+        )
+        .yellow()
+        .bold()
+    );
+    println!("{}", "═".repeat(60).bright_black());
+
+    for row in grid
+        .cells
+        .chunks(grid.size)
+        .zip(grid.changed.chunks(grid.size))
+    {
+        let line: String = row
+            .0
+            .iter()
+            .zip(row.1)
+            .map(|(&strategy, &changed)| {
+                let block = if changed { "▓▓" } else { "██" };
+                block.color(chart::PALETTE[strategy]).to_string()
+            })
+            .collect();
+        println!("{}", line);
+    }
+
+    let shares = grid.shares();
+    let legend: Vec<String> = grid
+        .names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            format!(
+                "{} {} {:.1}%",
+                "██".color(chart::PALETTE[i]),
+                name,
+                shares[i] * 100.0
+            )
+        })
+        .collect();
+    println!("{}", legend.join("   "));
+    println!(
+        "{}",
+        format!(
+            "▓▓ switched strategy this generation ({} cells)",
+            grid.changed.iter().filter(|&&c| c).count()
+        )
+        .bright_black()
+    );
+}
+
+/// Runs the grid for the given number of generations, drawing each one, and
+/// stops early once no cell changes any more.
+pub fn run(settings: &Settings) {
+    let mut grid = Grid::new(settings);
+    let mut shares = vec![grid.shares()];
+    let mut stable_since = None;
+
+    print_grid(&grid, 0, settings.generations);
+    for generation in 1..=settings.generations {
+        thread::sleep(FRAME_DELAY);
+        let changed = grid.step();
+        shares.push(grid.shares());
+        print_grid(&grid, generation, settings.generations);
+        if changed == 0 {
+            stable_since = Some(generation - 1);
+            break;
+        }
+    }
+
+    println!();
+    if let Some(generation) = stable_since {
+        println!(
+            "{}",
+            format!("The grid has been stable since generation {}.", generation)
+                .bright_green()
+                .bold()
+        );
+        println!();
+    }
+
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "SHARE OF THE GRID OVER TIME".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    let series: Vec<chart::Series> = grid
+        .names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| chart::Series {
+            label: name.clone(),
+            values: shares.iter().map(|s| s[i] * 100.0).collect(),
+            color: chart::PALETTE[i],
+        })
+        .collect();
+    chart::print_line_chart(&series, 10, &|value| format!("{:.0}%", value));
+    println!(
+        "  {}",
+        format!("generation 0 to {}", shares.len() - 1).bright_black()
+    );
+    println!();
+    println!(
+        "{}",
+        format!(
+            "{0}x{0} grid, {1} neighborhood, {2} per encounter, seed {3}",
+            grid.size,
+            settings.neighborhood.name(),
+            if settings.rounds == 1 {
+                "1 round".to_string()
+            } else {
+                format!("{} rounds", settings.rounds)
+            },
+            settings.seed
+        )
+        .bright_black()
+    );
+}
@@ -0,0 +1,21 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// `path` with an extra extension, e.g. `game_stats.json.bak`.
+pub fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Writes to a temporary file next to `path` and renames it into place, so a
+/// crash halfway through never leaves a truncated file behind.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let temp = sibling(path, "tmp");
+    let mut file = File::create(&temp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temp, path)
+}
@@ -0,0 +1,576 @@
+use crate::bot;
+use crate::Move;
+use rand::{Rng, RngCore};
+
+/// A computer opponent. `history` holds one `(opponent, own)` pair per
+/// completed round, oldest first. All randomness must come from `rng` so a
+/// seeded game can be replayed exactly.
+pub trait Strategy {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    fn next_move(&mut self, history: &[(Move, Move)], rng: &mut dyn RngCore) -> Move;
+    fn reset(&mut self) {}
+
+    /// Catches a fresh instance up with a game already under way, such as a
+    /// resumed one, by showing it every earlier position in turn.
+    fn resume(&mut self, history: &[(Move, Move)], rng: &mut dyn RngCore) {
+        self.reset();
+        for seen in 0..history.len() {
+            self.next_move(&history[..seen], rng);
+        }
+    }
+}
+
+/// Every opponent the menus can offer, in display order.
+pub fn registry() -> Vec<Box<dyn Strategy>> {
+    let mut strategies = difficulty_levels();
+    strategies.extend(classic_strategies());
+    strategies.extend(bot::strategies());
+    strategies
+}
+
+/// Command-line friendly form of a strategy name, e.g. "tit-for-tat".
+pub fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Looks a strategy up by name, ignoring case and punctuation.
+pub fn find(name: &str) -> Option<Box<dyn Strategy>> {
+    let wanted = slug(name);
+    registry().into_iter().find(|s| slug(s.name()) == wanted)
+}
+
+pub fn difficulty_levels() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(Easy),
+        Box::new(Medium),
+        Box::new(Hard),
+        Box::new(Legendary),
+    ]
+}
+
+/// The canonical strategies from Axelrod's iterated PD tournaments.
+pub fn classic_strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(TitForTat),
+        Box::new(TitForTwoTats),
+        Box::new(GrimTrigger),
+        Box::new(Pavlov),
+        Box::new(GenerousTitForTat),
+        Box::new(SuspiciousTitForTat),
+        Box::new(AlwaysCooperate),
+        Box::new(AlwaysDefect),
+        Box::new(Joss),
+        Box::new(Tester::new()),
+        Box::new(Prober),
+        Box::new(Gradual::new()),
+        Box::new(RandomMove),
+    ]
+}
+
+fn opponent_defect_rate(history: &[(Move, Move)]) -> f32 {
+    history
+        .iter()
+        .filter(|(opponent, _)| *opponent == Move::Defect)
+        .count() as f32
+        / history.len() as f32
+}
+
+pub struct Easy;
+
+impl Strategy for Easy {
+    fn name(&self) -> &str {
+        "Easy"
+    }
+
+    fn description(&self) -> &str {
+        "Computer cooperates 70% of the time"
+    }
+
+    fn next_move(&mut self, _history: &[(Move, Move)], rng: &mut dyn RngCore) -> Move {
+        if rng.gen_bool(0.7) {
+            Move::Cooperate
+        } else {
+            Move::Defect
+        }
+    }
+}
+
+pub struct Medium;
+
+impl Strategy for Medium {
+    fn name(&self) -> &str {
+        "Medium"
+    }
+
+    fn description(&self) -> &str {
+        "Computer uses pure tit-for-tat"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], rng: &mut dyn RngCore) -> Move {
+        match history.last() {
+            None => Move::Cooperate,
+            Some(&(last_opponent_move, _)) => {
+                if rng.gen_bool(0.85) {
+                    last_opponent_move
+                } else {
+                    Move::Defect
+                }
+            }
+        }
+    }
+}
+
+pub struct Hard;
+
+impl Strategy for Hard {
+    fn name(&self) -> &str {
+        "Hard"
+    }
+
+    fn description(&self) -> &str {
+        "Computer defects strategically 40% of the time"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], rng: &mut dyn RngCore) -> Move {
+        if history.is_empty() {
+            if rng.gen_bool(0.6) {
+                Move::Cooperate
+            } else {
+                Move::Defect
+            }
+        } else if opponent_defect_rate(history) > 0.4 {
+            Move::Defect
+        } else if rng.gen_bool(0.6) {
+            Move::Cooperate
+        } else {
+            Move::Defect
+        }
+    }
+}
+
+pub struct Legendary;
+
+impl Strategy for Legendary {
+    fn name(&self) -> &str {
+        "Legendary"
+    }
+
+    fn description(&self) -> &str {
+        "Computer is unpredictable and ruthless"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], rng: &mut dyn RngCore) -> Move {
+        let Some(&(last_opponent_move, _)) = history.last() else {
+            return if rng.gen_bool(0.5) {
+                Move::Cooperate
+            } else {
+                Move::Defect
+            };
+        };
+
+        let mut strategy = if opponent_defect_rate(history) > 0.3
+            || last_opponent_move == Move::Defect
+            || rng.gen_bool(0.5)
+        {
+            Move::Defect
+        } else {
+            Move::Cooperate
+        };
+
+        if rng.gen_bool(0.15) {
+            strategy = if strategy == Move::Cooperate {
+                Move::Defect
+            } else {
+                Move::Cooperate
+            };
+        }
+
+        strategy
+    }
+}
+
+pub struct TitForTat;
+
+impl Strategy for TitForTat {
+    fn name(&self) -> &str {
+        "Tit-for-Tat"
+    }
+
+    fn description(&self) -> &str {
+        "Cooperates first, then copies your last move"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        match history.last() {
+            None => Move::Cooperate,
+            Some(&(last_opponent_move, _)) => last_opponent_move,
+        }
+    }
+}
+
+pub struct TitForTwoTats;
+
+impl Strategy for TitForTwoTats {
+    fn name(&self) -> &str {
+        "Tit-for-Two-Tats"
+    }
+
+    fn description(&self) -> &str {
+        "Defects only after you defect twice in a row"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        match history {
+            [.., (Move::Defect, _), (Move::Defect, _)] => Move::Defect,
+            _ => Move::Cooperate,
+        }
+    }
+}
+
+pub struct GrimTrigger;
+
+impl Strategy for GrimTrigger {
+    fn name(&self) -> &str {
+        "Grim Trigger"
+    }
+
+    fn description(&self) -> &str {
+        "Cooperates until you defect once, then never forgives"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        if history
+            .iter()
+            .any(|(opponent, _)| *opponent == Move::Defect)
+        {
+            Move::Defect
+        } else {
+            Move::Cooperate
+        }
+    }
+}
+
+pub struct Pavlov;
+
+impl Strategy for Pavlov {
+    fn name(&self) -> &str {
+        "Pavlov"
+    }
+
+    fn description(&self) -> &str {
+        "Win-Stay Lose-Shift: repeats its move after a good round, switches after a bad one"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        match history.last() {
+            None => Move::Cooperate,
+            Some(&(opponent, own)) if opponent == own => Move::Cooperate,
+            Some(_) => Move::Defect,
+        }
+    }
+}
+
+pub struct GenerousTitForTat;
+
+impl Strategy for GenerousTitForTat {
+    fn name(&self) -> &str {
+        "Generous Tit-for-Tat"
+    }
+
+    fn description(&self) -> &str {
+        "Tit-for-Tat that forgives a defection one time in three"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], rng: &mut dyn RngCore) -> Move {
+        match history.last() {
+            Some(&(Move::Defect, _)) if !rng.gen_bool(1.0 / 3.0) => Move::Defect,
+            _ => Move::Cooperate,
+        }
+    }
+}
+
+pub struct SuspiciousTitForTat;
+
+impl Strategy for SuspiciousTitForTat {
+    fn name(&self) -> &str {
+        "Suspicious Tit-for-Tat"
+    }
+
+    fn description(&self) -> &str {
+        "Defects first, then copies your last move"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        match history.last() {
+            None => Move::Defect,
+            Some(&(last_opponent_move, _)) => last_opponent_move,
+        }
+    }
+}
+
+pub struct AlwaysCooperate;
+
+impl Strategy for AlwaysCooperate {
+    fn name(&self) -> &str {
+        "Always Cooperate"
+    }
+
+    fn description(&self) -> &str {
+        "Cooperates no matter what"
+    }
+
+    fn next_move(&mut self, _history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        Move::Cooperate
+    }
+}
+
+pub struct AlwaysDefect;
+
+impl Strategy for AlwaysDefect {
+    fn name(&self) -> &str {
+        "Always Defect"
+    }
+
+    fn description(&self) -> &str {
+        "Defects no matter what"
+    }
+
+    fn next_move(&mut self, _history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        Move::Defect
+    }
+}
+
+pub struct Joss;
+
+impl Strategy for Joss {
+    fn name(&self) -> &str {
+        "Joss"
+    }
+
+    fn description(&self) -> &str {
+        "Tit-for-Tat that sneaks in a defection 10% of the time"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], rng: &mut dyn RngCore) -> Move {
+        match history.last() {
+            Some(&(Move::Defect, _)) => Move::Defect,
+            _ if rng.gen_bool(0.1) => Move::Defect,
+            _ => Move::Cooperate,
+        }
+    }
+}
+
+pub struct Tester {
+    retaliated_at: Option<usize>,
+}
+
+impl Tester {
+    pub fn new() -> Self {
+        Tester { retaliated_at: None }
+    }
+}
+
+impl Strategy for Tester {
+    fn name(&self) -> &str {
+        "Tester"
+    }
+
+    fn description(&self) -> &str {
+        "Opens with a defection; backs off into Tit-for-Tat if you retaliate, otherwise exploits you"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        if history.is_empty() {
+            return Move::Defect;
+        }
+
+        if self.retaliated_at.is_none() && history.last().unwrap().0 == Move::Defect {
+            self.retaliated_at = Some(history.len());
+        }
+
+        match self.retaliated_at {
+            // Apologize once, then settle into Tit-for-Tat.
+            Some(round) if round == history.len() => Move::Cooperate,
+            Some(_) => history.last().unwrap().0,
+            None if history.len() < 3 || history.len().is_multiple_of(2) => Move::Cooperate,
+            None => Move::Defect,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.retaliated_at = None;
+    }
+}
+
+pub struct Prober;
+
+impl Strategy for Prober {
+    fn name(&self) -> &str {
+        "Prober"
+    }
+
+    fn description(&self) -> &str {
+        "Opens D, C, C; exploits you forever if you didn't retaliate, otherwise plays Tit-for-Tat"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        match history.len() {
+            0 => Move::Defect,
+            1 | 2 => Move::Cooperate,
+            _ if history[1].0 == Move::Cooperate && history[2].0 == Move::Cooperate => {
+                Move::Defect
+            }
+            _ => history.last().unwrap().0,
+        }
+    }
+}
+
+pub struct Gradual {
+    punishments_left: u32,
+    calm_left: u32,
+}
+
+impl Gradual {
+    pub fn new() -> Self {
+        Gradual {
+            punishments_left: 0,
+            calm_left: 0,
+        }
+    }
+}
+
+impl Strategy for Gradual {
+    fn name(&self) -> &str {
+        "Gradual"
+    }
+
+    fn description(&self) -> &str {
+        "Answers your n-th defection with n defections, then two cooperations to calm down"
+    }
+
+    fn next_move(&mut self, history: &[(Move, Move)], _rng: &mut dyn RngCore) -> Move {
+        if self.punishments_left > 0 {
+            self.punishments_left -= 1;
+            return Move::Defect;
+        }
+        if self.calm_left > 0 {
+            self.calm_left -= 1;
+            return Move::Cooperate;
+        }
+
+        match history.last() {
+            Some(&(Move::Defect, _)) => {
+                let defections = history
+                    .iter()
+                    .filter(|(opponent, _)| *opponent == Move::Defect)
+                    .count() as u32;
+                self.punishments_left = defections - 1;
+                self.calm_left = 2;
+                Move::Defect
+            }
+            _ => Move::Cooperate,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.punishments_left = 0;
+        self.calm_left = 0;
+    }
+}
+
+pub struct RandomMove;
+
+impl Strategy for RandomMove {
+    fn name(&self) -> &str {
+        "Random"
+    }
+
+    fn description(&self) -> &str {
+        "Flips a fair coin every round"
+    }
+
+    fn next_move(&mut self, _history: &[(Move, Move)], rng: &mut dyn RngCore) -> Move {
+        if rng.gen_bool(0.5) {
+            Move::Cooperate
+        } else {
+            Move::Defect
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn parse(moves: &str) -> Vec<Move> {
+        moves
+            .chars()
+            .map(|c| match c {
+                'C' => Move::Cooperate,
+                _ => Move::Defect,
+            })
+            .collect()
+    }
+
+    /// The moves `strategy` makes against an opponent playing `opponent`,
+    /// written as a string of `C` and `D`.
+    fn replies(strategy: &mut dyn Strategy, opponent: &str) -> String {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut history = Vec::new();
+        let mut own = String::new();
+        for their_move in parse(opponent) {
+            let choice = strategy.next_move(&history, &mut rng);
+            own.push(match choice {
+                Move::Cooperate => 'C',
+                Move::Defect => 'D',
+            });
+            history.push((their_move, choice));
+        }
+        own
+    }
+
+    #[test]
+    fn tester_exploits_a_pushover() {
+        assert_eq!(replies(&mut Tester::new(), "CCCCCC"), "DCCDCD");
+    }
+
+    #[test]
+    fn tester_apologizes_after_retaliation() {
+        assert_eq!(replies(&mut Tester::new(), "CDDCC"), "DCCDC");
+    }
+
+    #[test]
+    fn prober_exploits_a_pushover() {
+        assert_eq!(replies(&mut Prober, "CCCCC"), "DCCDD");
+    }
+
+    #[test]
+    fn prober_plays_tit_for_tat_after_retaliation() {
+        assert_eq!(replies(&mut Prober, "CDCDC"), "DCCCD");
+    }
+
+    #[test]
+    fn gradual_punishes_each_defection_harder() {
+        assert_eq!(replies(&mut Gradual::new(), "CDCCDCCCCC"), "CCDCCDDCCC");
+    }
+
+    #[test]
+    fn reset_forgets_earlier_games() {
+        let mut gradual = Gradual::new();
+        replies(&mut gradual, "CD");
+        gradual.reset();
+        assert_eq!(replies(&mut gradual, "CC"), "CC");
+
+        let mut tester = Tester::new();
+        replies(&mut tester, "CDD");
+        tester.reset();
+        assert_eq!(replies(&mut tester, "CCCCCC"), "DCCDCD");
+    }
+}
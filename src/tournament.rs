@@ -0,0 +1,404 @@
+use crate::chart;
+use crate::games::GameKind;
+use crate::payoff::PayoffMatrix;
+use crate::strategy::{self, Strategy};
+use crate::{GameState, Move};
+use colored::{Color, Colorize};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub const DEFAULT_ROUNDS: u32 = 200;
+pub const DEFAULT_REPETITIONS: u32 = 5;
+
+pub struct TournamentResult {
+    pub names: Vec<String>,
+    pub rounds: u32,
+    pub repetitions: u32,
+    pub noise: f64,
+    pub seed: u64,
+    /// Average points per round earned by the row strategy against the column strategy.
+    pub head_to_head: Vec<Vec<f32>>,
+    /// Twice each strategy's total points, which keeps the half points from
+    /// splitting a self-play match between its two seats exact.
+    pub doubled_scores: Vec<i64>,
+    pub cooperation_rates: Vec<f32>,
+}
+
+impl TournamentResult {
+    pub fn total_score(&self, index: usize) -> f64 {
+        self.doubled_scores[index] as f64 / 2.0
+    }
+
+    pub fn average_score(&self, index: usize) -> f32 {
+        let rounds_played = self.names.len() as f32 * self.rounds as f32 * self.repetitions as f32;
+        self.total_score(index) as f32 / rounds_played
+    }
+
+    /// Strategy indices ordered from best to worst total score.
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.names.len()).collect();
+        order.sort_by(|&a, &b| self.doubled_scores[b].cmp(&self.doubled_scores[a]));
+        order
+    }
+}
+
+/// Plays `a` (as the player) against `b` (as the computer) for `rounds` rounds.
+pub fn play_match(
+    a: &mut dyn Strategy,
+    b: &mut dyn Strategy,
+    rounds: u32,
+    payoffs: PayoffMatrix,
+    noise: f64,
+    seed: u64,
+) -> GameState {
+    let mut state = GameState::new(
+        rounds,
+        b.name(),
+        GameKind::PrisonersDilemma,
+        payoffs,
+        noise,
+        seed,
+    );
+    a.reset();
+    b.reset();
+
+    let mut mirrored: Vec<(Move, Move)> = Vec::with_capacity(rounds as usize);
+    for _ in 0..rounds {
+        state.round += 1;
+
+        let intended = (
+            a.next_move(&mirrored, &mut state.rng),
+            b.next_move(&state.history, &mut state.rng),
+        );
+        let a_move = state.tremble(intended.0);
+        let b_move = state.tremble(intended.1);
+        let (a_points, b_points) = state.calculate_payoff(a_move, b_move);
+
+        state.player_score += a_points;
+        state.computer_score += b_points;
+        state.history.push((a_move, b_move));
+        state.intended.push(intended);
+        mirrored.push((b_move, a_move));
+    }
+
+    state
+}
+
+/// The two seats of a match between strategies `i <= j`. A strategy meeting
+/// itself plays its twin, a second instance kept for just that.
+pub fn seats<'a>(
+    strategies: &'a mut [Box<dyn Strategy>],
+    twins: &'a mut [Box<dyn Strategy>],
+    i: usize,
+    j: usize,
+) -> (&'a mut dyn Strategy, &'a mut dyn Strategy) {
+    if i == j {
+        (strategies[i].as_mut(), twins[i].as_mut())
+    } else {
+        let (left, right) = strategies.split_at_mut(j);
+        (left[i].as_mut(), right[0].as_mut())
+    }
+}
+
+/// Round-robin in which every registered strategy meets every other, and itself.
+/// Each match draws its own seed from `seed`, so the whole tournament is reproducible.
+pub fn run(
+    rounds: u32,
+    repetitions: u32,
+    payoffs: PayoffMatrix,
+    noise: f64,
+    seed: u64,
+) -> TournamentResult {
+    let mut seeds = ChaCha8Rng::seed_from_u64(seed);
+    // Built once, so bots keep one process for the whole tournament.
+    let mut players = strategy::registry();
+    let mut twins = strategy::registry();
+    let count = players.len();
+
+    // Doubled, see `TournamentResult::doubled_scores`.
+    let mut points = vec![vec![0i64; count]; count];
+    let mut cooperations = vec![0u64; count];
+    let mut moves = vec![0u64; count];
+
+    for i in 0..count {
+        for j in i..count {
+            for _ in 0..repetitions {
+                let (a, b) = seats(&mut players, &mut twins, i, j);
+                let state = play_match(a, b, rounds, payoffs, noise, seeds.gen());
+
+                for &(a_move, b_move) in &state.history {
+                    if a_move == Move::Cooperate {
+                        cooperations[i] += 1;
+                    }
+                    if b_move == Move::Cooperate {
+                        cooperations[j] += 1;
+                    }
+                }
+                moves[i] += state.history.len() as u64;
+                moves[j] += state.history.len() as u64;
+
+                if i == j {
+                    // Both seats belong to the same strategy; count it once.
+                    points[i][i] += (state.player_score + state.computer_score) as i64;
+                } else {
+                    points[i][j] += 2 * state.player_score as i64;
+                    points[j][i] += 2 * state.computer_score as i64;
+                }
+            }
+        }
+    }
+
+    let rounds_per_pairing = rounds as f32 * repetitions as f32;
+    TournamentResult {
+        names: players.iter().map(|s| s.name().to_string()).collect(),
+        rounds,
+        repetitions,
+        noise,
+        seed,
+        head_to_head: points
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&p| p as f32 / 2.0 / rounds_per_pairing)
+                    .collect()
+            })
+            .collect(),
+        doubled_scores: points.iter().map(|row| row.iter().sum()).collect(),
+        cooperation_rates: cooperations
+            .iter()
+            .zip(&moves)
+            .map(|(&c, &m)| if m == 0 { 0.0 } else { c as f32 / m as f32 * 100.0 })
+            .collect(),
+    }
+}
+
+pub fn print_results(result: &TournamentResult) {
+    let ranking = result.ranking();
+    let name_width = result.names.iter().map(|n| n.len()).max().unwrap_or(0);
+
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "TOURNAMENT STANDINGS".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    println!(
+        "  {}",
+        format!(
+            "{:>4}  {:<width$}  {:>9}  {:>9}  {:>7}",
+            "Rank",
+            "Strategy",
+            "Total",
+            "Avg/Rnd",
+            "Coop %",
+            width = name_width
+        )
+        .cyan()
+        .bold()
+    );
+    for (rank, &i) in ranking.iter().enumerate() {
+        let line = format!(
+            "{:>4}  {:<width$}  {:>9.1}  {:>9.3}  {:>6.1}%",
+            rank + 1,
+            result.names[i],
+            result.total_score(i),
+            result.average_score(i),
+            result.cooperation_rates[i],
+            width = name_width
+        );
+        let line = match rank {
+            0 => line.bright_green().bold(),
+            _ if rank + 1 == ranking.len() => line.bright_red(),
+            _ => line.normal(),
+        };
+        println!("  {}", line);
+    }
+    println!();
+
+    println!("{}", "═".repeat(60).bright_black());
+    println!("{}", "AVERAGE POINTS PER ROUND".yellow().bold());
+    println!("{}", "═".repeat(60).bright_black());
+    let bars: Vec<(String, f64, Color)> = ranking
+        .iter()
+        .enumerate()
+        .map(|(rank, &i)| {
+            let color = match rank {
+                0 => Color::BrightGreen,
+                _ if rank + 1 == ranking.len() => Color::BrightRed,
+                _ => Color::Cyan,
+            };
+            (
+                result.names[i].clone(),
+                result.average_score(i) as f64,
+                color,
+            )
+        })
+        .collect();
+    chart::print_bar_chart(&bars, &|value| format!("{:.3}", value));
+    println!();
+
+    println!("{}", "═".repeat(60).bright_black());
+    println!(
+        "{}",
+        "HEAD-TO-HEAD (Average points per round, row vs column)"
+            .yellow()
+            .bold()
+    );
+    println!("{}", "═".repeat(60).bright_black());
+    let mut header = format!("  {:>4}  {:<width$} ", "", "", width = name_width);
+    for rank in 1..=ranking.len() {
+        header.push_str(&format!("{:>6}", format!("#{}", rank)));
+    }
+    println!("{}", header.cyan().bold());
+    for (rank, &i) in ranking.iter().enumerate() {
+        print!(
+            "  {:>4}  {:<width$} ",
+            format!("#{}", rank + 1).cyan(),
+            result.names[i],
+            width = name_width
+        );
+        for &j in &ranking {
+            let score = result.head_to_head[i][j];
+            let cell = format!("{:>6.2}", score);
+            let cell = if score > result.head_to_head[j][i] {
+                cell.bright_green()
+            } else if score < result.head_to_head[j][i] {
+                cell.bright_red()
+            } else {
+                cell.yellow()
+            };
+            print!("{}", cell);
+        }
+        println!();
+    }
+    println!();
+    println!(
+        "{}",
+        format!(
+            "{} strategies, {} rounds per match, {} repetitions, {:.0}% noise, seed {}",
+            result.names.len(),
+            result.rounds,
+            result.repetitions,
+            result.noise * 100.0,
+            result.seed
+        )
+        .bright_black()
+    );
+}
+
+/// Round-by-round log of a single match played by `play_match`.
+pub fn print_match(state: &GameState, player: &str) {
+    let name_width = player.len().max(state.opponent.len()).max(9);
+    let move_str = |m: Move| {
+        let label = format!("{:<width$}", state.game.move_label(m), width = name_width);
+        if m == Move::Cooperate {
+            label.green()
+        } else {
+            label.red()
+        }
+    };
+
+    println!("{}", "═".repeat(60).bright_black());
+    println!(
+        "{}",
+        format!("{} vs {}", player, state.opponent).yellow().bold()
+    );
+    println!("{}", "═".repeat(60).bright_black());
+    println!(
+        "  {}",
+        format!(
+            "{:>5}  {:<width$}  {:<width$}  {:>6}",
+            "Round",
+            player,
+            state.opponent,
+            "Points",
+            width = name_width
+        )
+        .cyan()
+        .bold()
+    );
+    for (round, &(a_move, b_move)) in state.history.iter().enumerate() {
+        let (a_points, b_points) = state.calculate_payoff(a_move, b_move);
+        // Games recorded before noise existed have no intended moves.
+        let (a_meant, b_meant) = state.intended.get(round).copied().unwrap_or((a_move, b_move));
+        println!(
+            "  {:>5}  {}{} {}{} {:>6}",
+            round + 1,
+            move_str(a_move),
+            noise_mark(a_meant != a_move),
+            move_str(b_move),
+            noise_mark(b_meant != b_move),
+            format!("{} / {}", a_points, b_points)
+        );
+    }
+    println!("{}", "═".repeat(60).bright_black());
+
+    let (player_color, opponent_color) = if state.player_score > state.computer_score {
+        (colored::Color::BrightGreen, colored::Color::BrightRed)
+    } else if state.player_score < state.computer_score {
+        (colored::Color::BrightRed, colored::Color::BrightGreen)
+    } else {
+        (colored::Color::Yellow, colored::Color::Yellow)
+    };
+    println!(
+        "  {} {}",
+        format!("{}:", player).cyan().bold(),
+        state.player_score.to_string().color(player_color)
+    );
+    println!(
+        "  {} {}",
+        format!("{}:", state.opponent).magenta().bold(),
+        state.computer_score.to_string().color(opponent_color)
+    );
+    if state.noise > 0.0 {
+        println!(
+            "{}",
+            format!(
+                "~ marks a move flipped by noise ({:.0}%)",
+                state.noise * 100.0
+            )
+            .magenta()
+        );
+    }
+    println!();
+    println!("{}", format!("Seed: {}", state.seed).bright_black());
+}
+
+fn noise_mark(flipped: bool) -> colored::ColoredString {
+    if flipped {
+        "~".magenta().bold()
+    } else {
+        " ".normal()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(result: &TournamentResult, name: &str) -> usize {
+        result.names.iter().position(|n| n == name).unwrap()
+    }
+
+    #[test]
+    fn self_play_counts_once_for_the_strategy() {
+        let result = run(10, 1, PayoffMatrix::CLASSIC, 0.0, 1);
+        let tft = index(&result, "Tit-for-Tat");
+        let defect = index(&result, "Always Defect");
+
+        // Ten rounds of mutual cooperation, split between the two seats.
+        assert_eq!(result.head_to_head[tft][tft], 3.0);
+        // Suckered once, then nine rounds of mutual defection.
+        assert_eq!(result.head_to_head[tft][defect], 0.9);
+        assert_eq!(result.head_to_head[defect][tft], 1.4);
+        assert_eq!(result.head_to_head[defect][defect], 1.0);
+    }
+
+    #[test]
+    fn totals_keep_half_points() {
+        // Noise makes self-play matches with odd totals, such as 5 + 0.
+        let result = run(10, 2, PayoffMatrix::CLASSIC, 0.2, 7);
+        for i in 0..result.names.len() {
+            let points: f64 = result.head_to_head[i].iter().map(|&p| p as f64 * 20.0).sum();
+            assert!((result.total_score(i) - points).abs() < 1e-3);
+        }
+        assert!(result.doubled_scores.iter().any(|&doubled| doubled % 2 == 1));
+    }
+}